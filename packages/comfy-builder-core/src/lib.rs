@@ -2,7 +2,11 @@ mod macros;
 pub mod node;
 pub mod prelude;
 pub mod registry;
+pub mod scheduler;
 pub mod types;
 
 pub use candle_core as candle;
+pub use half;
+#[cfg(feature = "image")]
+pub use image;
 pub use numpy;
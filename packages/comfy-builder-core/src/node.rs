@@ -1,3 +1,4 @@
+use crate::types::torch::OutputPlacement;
 use pyo3::types::{PyCFunction, PyDict, PyList, PyTuple};
 use pyo3::{Bound, PyAny, PyErr, PyResult, Python};
 use std::error::Error;
@@ -13,6 +14,10 @@ pub trait Node: Default {
     const IS_OUTPUT_NODE: bool = false;
     const IS_DEPRECATED: bool = false;
 
+    /// Whether outputs are handed back on the cpu with their declared dtype, or restore the device
+    /// and dtype of the inputs they were decoded from.
+    const OUTPUT_PLACEMENT: OutputPlacement = OutputPlacement::Declared;
+
     fn new() -> Self {
         Default::default()
     }
@@ -1,4 +1,16 @@
 pub use crate::node::{In, Kwargs, Node, NodeFunctionProvider, Out};
 pub use crate::types::comfy_type::{AsInput, ComfyType};
-pub use crate::types::{image::Image, latent::Latent, mask::Mask};
-pub use comfy_builder_macros::{Enum, NodeInput, NodeOutput, boostrap, node};
+pub use crate::types::dynamic_options::DynamicOptions;
+pub use crate::types::{
+    audio::Audio,
+    conditioning::Conditioning,
+    guider::Guider,
+    handle::{Clip, ClipVision, ControlNet, Gligen, Model, StyleModel, UpscaleModel, Vae},
+    image::Image,
+    latent::Latent,
+    mask::Mask,
+    sampler::Sampler,
+    torch::OutputPlacement,
+    video::Video,
+};
+pub use comfy_builder_macros::{ComfyCustomType, DynamicOptions, Enum, NodeInput, NodeOutput, boostrap, node};
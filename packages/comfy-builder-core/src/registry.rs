@@ -1,7 +1,20 @@
 use crate::node::NodeFunctionProvider;
 use pyo3::prelude::PyAnyMethods;
-use pyo3::types::{PyCFunction, PyDict, PyDictMethods};
+use pyo3::types::{PyCFunction, PyDict, PyDictMethods, PyModule};
 use pyo3::{Bound, PyAny, PyResult, Python};
+use std::sync::OnceLock;
+
+static API_VERSION: OnceLock<&'static str> = OnceLock::new();
+
+/// Record the `comfy_api` version the node pack was bootstrapped with, done by `boostrap!`.
+pub fn set_api_version(version: &'static str) {
+    let _ = API_VERSION.set(version);
+}
+
+/// The `comfy_api` module of the version the node pack was bootstrapped with, `latest` otherwise.
+pub fn api_module(python: Python) -> PyResult<Bound<PyModule>> {
+    python.import(format!("comfy_api.{}", API_VERSION.get().copied().unwrap_or("latest")))
+}
 
 type FactoryFn = for<'py> fn(python: Python<'py>) -> PyResult<(Bound<'py, PyCFunction>, Bound<'py, PyCFunction>)>;
 
@@ -15,6 +28,11 @@ pub struct EnumRegistration {
     pub name: &'static str,
 }
 
+#[derive(Debug)]
+pub struct CustomTypeRegistration {
+    pub name: &'static str,
+}
+
 impl NodeRegistration {
     pub const fn new<T: NodeFunctionProvider>() -> Self {
         Self {
@@ -42,3 +60,4 @@ impl NodeRegistration {
 
 inventory::collect!(NodeRegistration);
 inventory::collect!(EnumRegistration);
+inventory::collect!(CustomTypeRegistration);
@@ -0,0 +1,419 @@
+//! Noise schedules producing [`Sigmas`], ported from ComfyUI's `comfy.samplers` and k-diffusion.
+//!
+//! Schedules that depend on the model, such as [`simple`], [`sgm_uniform`] and [`beta`], take a
+//! [`DiscreteSchedule`] describing the sigmas the model was trained on.
+
+use crate::types::image::torch_to_candle;
+use crate::types::handle::Model;
+use crate::types::sigmas::Sigmas;
+use candle_core::{Device, WithDType};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::{PyResult, Python};
+
+/// The noise levels of a discrete-time model, one per training timestep, ordered from low to high.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscreteSchedule {
+    sigmas: Vec<f64>,
+}
+
+impl DiscreteSchedule {
+    pub fn from_sigmas(sigmas: Vec<f64>) -> Self {
+        Self { sigmas }
+    }
+
+    /// The `scaled_linear` beta schedule used by SD1.x and SDXL.
+    pub fn scaled_linear(timesteps: usize, beta_start: f64, beta_end: f64) -> Self {
+        let mut alphas_cumprod = 1.0;
+
+        let sigmas = linspace(beta_start.sqrt(), beta_end.sqrt(), timesteps)
+            .into_iter()
+            .map(|beta| {
+                alphas_cumprod *= 1.0 - beta * beta;
+                ((1.0 - alphas_cumprod) / alphas_cumprod).sqrt()
+            })
+            .collect();
+
+        Self { sigmas }
+    }
+
+    /// Read the schedule from the `model_sampling` object of a loaded model.
+    pub fn from_model(python: Python, model: &Model) -> PyResult<Self> {
+        let sigmas = model
+            .bind(python)
+            .call_method1("get_model_object", ("model_sampling",))?
+            .getattr("sigmas")?
+            .call_method0("float")?;
+
+        let sigmas = torch_to_candle::<f32>(sigmas, &Device::Cpu)?
+            .to_vec1::<f32>()
+            .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))?;
+
+        Ok(Self::from_sigmas(sigmas.into_iter().map(f64::from).collect()))
+    }
+
+    pub fn sigmas(&self) -> &[f64] {
+        &self.sigmas
+    }
+
+    /// The lowest sigma, or `None` for an empty schedule.
+    pub fn sigma_min(&self) -> Option<f64> {
+        self.sigmas.first().copied()
+    }
+
+    /// The highest sigma, or `None` for an empty schedule.
+    pub fn sigma_max(&self) -> Option<f64> {
+        self.sigmas.last().copied()
+    }
+
+    /// The lowest and highest sigma, failing on an empty schedule the model schedules can't use.
+    fn bounds(&self) -> candle_core::Result<(f64, f64)> {
+        match (self.sigma_min(), self.sigma_max()) {
+            (Some(sigma_min), Some(sigma_max)) => Ok((sigma_min, sigma_max)),
+            _ => candle_core::bail!("the model schedule has no sigmas"),
+        }
+    }
+
+    /// The timestep whose sigma is closest to `sigma` in log space.
+    pub fn timestep(&self, sigma: f64) -> f64 {
+        let log_sigma = sigma.ln();
+
+        self.sigmas
+            .iter()
+            .map(|sigma| (log_sigma - sigma.ln()).abs())
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index as f64)
+            .unwrap_or_default()
+    }
+
+    /// The sigma at a possibly fractional `timestep`, interpolated in log space, or `None` for an empty schedule.
+    pub fn sigma(&self, timestep: f64) -> Option<f64> {
+        let last = self.sigmas.len().checked_sub(1)?;
+        let timestep = timestep.clamp(0.0, last as f64);
+        let (low, high) = (timestep.floor() as usize, timestep.ceil() as usize);
+        let weight = timestep.fract();
+
+        Some(((1.0 - weight) * self.sigmas[low].ln() + weight * self.sigmas[high].ln()).exp())
+    }
+}
+
+impl Default for DiscreteSchedule {
+    fn default() -> Self {
+        Self::scaled_linear(1000, 0.00085, 0.012)
+    }
+}
+
+/// The models [`align_your_steps`] has optimized noise levels for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AysModel {
+    Sd1,
+    Sdxl,
+    Svd,
+}
+
+impl AysModel {
+    fn noise_levels(&self) -> [f64; 11] {
+        match self {
+            AysModel::Sd1 => [
+                14.6146412293,
+                6.4745760956,
+                3.8636745985,
+                2.6946151520,
+                1.8841921177,
+                1.3943805092,
+                0.9642583904,
+                0.6523686016,
+                0.3977456272,
+                0.1522232272,
+                0.0291671582,
+            ],
+            AysModel::Sdxl => [
+                14.6146412293,
+                6.3184485287,
+                3.7681790315,
+                2.1811480769,
+                1.3405244945,
+                0.8620721141,
+                0.5550693289,
+                0.3798540708,
+                0.2332364134,
+                0.1114188177,
+                0.0291671582,
+            ],
+            AysModel::Svd => [
+                700.00, 54.5, 15.886, 7.977, 4.248, 1.789, 0.981, 0.403, 0.173, 0.034, 0.002,
+            ],
+        }
+    }
+}
+
+fn linspace(start: f64, end: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => vec![],
+        1 => vec![start],
+        _ => (0..steps)
+            .map(|index| start + (end - start) * index as f64 / (steps - 1) as f64)
+            .collect(),
+    }
+}
+
+fn append_zero<T: WithDType>(mut sigmas: Vec<f64>) -> candle_core::Result<Sigmas<T>> {
+    sigmas.push(0.0);
+
+    Sigmas::from_values(&sigmas)
+}
+
+/// The schedule from Karras et al. (2022), `rho` is usually `7.0`.
+pub fn karras<T: WithDType>(steps: usize, sigma_min: f64, sigma_max: f64, rho: f64) -> candle_core::Result<Sigmas<T>> {
+    let min_inverse = sigma_min.powf(1.0 / rho);
+    let max_inverse = sigma_max.powf(1.0 / rho);
+
+    append_zero(
+        linspace(0.0, 1.0, steps)
+            .into_iter()
+            .map(|ramp| (max_inverse + ramp * (min_inverse - max_inverse)).powf(rho))
+            .collect(),
+    )
+}
+
+/// A schedule evenly spaced in log space.
+pub fn exponential<T: WithDType>(steps: usize, sigma_min: f64, sigma_max: f64) -> candle_core::Result<Sigmas<T>> {
+    append_zero(
+        linspace(sigma_max.ln(), sigma_min.ln(), steps)
+            .into_iter()
+            .map(f64::exp)
+            .collect(),
+    )
+}
+
+/// A schedule polynomial in log space, `rho` of `1.0` is the same as [`exponential`].
+pub fn polyexponential<T: WithDType>(
+    steps: usize,
+    sigma_min: f64,
+    sigma_max: f64,
+    rho: f64,
+) -> candle_core::Result<Sigmas<T>> {
+    append_zero(
+        linspace(1.0, 0.0, steps)
+            .into_iter()
+            .map(|ramp| (ramp.powf(rho) * (sigma_max.ln() - sigma_min.ln()) + sigma_min.ln()).exp())
+            .collect(),
+    )
+}
+
+/// Evenly spaced timesteps picked straight from the model schedule.
+pub fn simple<T: WithDType>(schedule: &DiscreteSchedule, steps: usize) -> candle_core::Result<Sigmas<T>> {
+    schedule.bounds()?;
+
+    let sigmas = schedule.sigmas();
+    let stride = sigmas.len() as f64 / steps as f64;
+
+    append_zero(
+        (0..steps)
+            .map(|step| sigmas[sigmas.len() - 1 - (step as f64 * stride) as usize])
+            .collect(),
+    )
+}
+
+/// Evenly spaced timesteps from `sigma_max` down to `sigma_min`, interpolated from the model schedule.
+pub fn normal<T: WithDType>(schedule: &DiscreteSchedule, steps: usize) -> candle_core::Result<Sigmas<T>> {
+    let (sigma_min, sigma_max) = schedule.bounds()?;
+    let start = schedule.timestep(sigma_max);
+    let end = schedule.timestep(sigma_min);
+
+    // Models whose schedule already ends at zero don't get another zero appended.
+    if schedule.sigma(end).is_some_and(|sigma| sigma.abs() <= 0.00001) {
+        let sigmas: Vec<f64> = linspace(start, end, steps + 1)
+            .into_iter()
+            .filter_map(|timestep| schedule.sigma(timestep))
+            .collect();
+
+        return Sigmas::from_values(&sigmas);
+    }
+
+    append_zero(
+        linspace(start, end, steps)
+            .into_iter()
+            .filter_map(|timestep| schedule.sigma(timestep))
+            .collect(),
+    )
+}
+
+/// Like [`normal`] but never reaching `sigma_min`, as used by the SGM family of models.
+pub fn sgm_uniform<T: WithDType>(schedule: &DiscreteSchedule, steps: usize) -> candle_core::Result<Sigmas<T>> {
+    let (sigma_min, sigma_max) = schedule.bounds()?;
+    let start = schedule.timestep(sigma_max);
+    let end = schedule.timestep(sigma_min);
+
+    append_zero(
+        linspace(start, end, steps + 1)
+            .into_iter()
+            .take(steps)
+            .filter_map(|timestep| schedule.sigma(timestep))
+            .collect(),
+    )
+}
+
+/// Timesteps distributed following a beta distribution, from "Beta Sampling is All You Need".
+///
+/// Both `alpha` and `beta` default to `0.6` in ComfyUI. Timesteps that round to the same
+/// value are only used once, so the schedule may have fewer than `steps` steps.
+pub fn beta<T: WithDType>(
+    schedule: &DiscreteSchedule,
+    steps: usize,
+    alpha: f64,
+    beta: f64,
+) -> candle_core::Result<Sigmas<T>> {
+    schedule.bounds()?;
+
+    let total_timesteps = (schedule.sigmas().len() - 1) as f64;
+    let mut sigmas = vec![];
+    let mut last = None;
+
+    for step in 0..steps {
+        let quantile = 1.0 - step as f64 / steps as f64;
+        let timestep = (beta_ppf(quantile, alpha, beta) * total_timesteps).round_ties_even();
+
+        if last != Some(timestep) {
+            sigmas.push(schedule.sigmas()[timestep as usize]);
+            last = Some(timestep);
+        }
+    }
+
+    append_zero(sigmas)
+}
+
+/// The optimized noise levels from "Align Your Steps", log-linearly interpolated to `steps` steps.
+pub fn align_your_steps<T: WithDType>(model: AysModel, steps: usize) -> candle_core::Result<Sigmas<T>> {
+    let levels = model.noise_levels();
+
+    let mut sigmas = match levels.len() == steps + 1 {
+        true => levels.to_vec(),
+        false => loglinear_interpolation(&levels, steps + 1),
+    };
+
+    if let Some(last) = sigmas.last_mut() {
+        *last = 0.0;
+    }
+
+    Sigmas::from_values(&sigmas)
+}
+
+fn loglinear_interpolation(levels: &[f64], count: usize) -> Vec<f64> {
+    let xs = linspace(0.0, 1.0, levels.len());
+    let ys: Vec<f64> = levels.iter().rev().map(|level| level.ln()).collect();
+
+    let mut interpolated: Vec<f64> = linspace(0.0, 1.0, count)
+        .into_iter()
+        .map(|x| {
+            let index = xs.partition_point(|value| *value <= x).clamp(1, xs.len() - 1);
+            let weight = (x - xs[index - 1]) / (xs[index] - xs[index - 1]);
+
+            (ys[index - 1] + weight * (ys[index] - ys[index - 1])).exp()
+        })
+        .collect();
+
+    interpolated.reverse();
+    interpolated
+}
+
+/// The natural logarithm of the gamma function, using the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (index, coefficient)| {
+            sum + coefficient / (x + index as f64)
+        });
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// The regularized incomplete beta function, evaluated with a continued fraction.
+fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    // The continued fraction converges quickly only on this side of the mean.
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - incomplete_beta(1.0 - x, b, a);
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp() / a;
+
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+
+    d = 1.0 / if d.abs() < tiny { tiny } else { d };
+
+    let mut result = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            d = 1.0 / if d.abs() < tiny { tiny } else { d };
+            c = 1.0 + numerator / c;
+            c = if c.abs() < tiny { tiny } else { c };
+            result *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+
+    front * result
+}
+
+/// The inverse of the beta distribution CDF, found by bisection.
+fn beta_ppf(quantile: f64, a: f64, b: f64) -> f64 {
+    let (mut low, mut high) = (0.0, 1.0);
+
+    if quantile <= 0.0 || quantile >= 1.0 {
+        return quantile.clamp(0.0, 1.0);
+    }
+
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+
+        match incomplete_beta(middle, a, b) < quantile {
+            true => low = middle,
+            false => high = middle,
+        }
+    }
+
+    // Drop the last bits of bisection noise, so exact quantiles such as the median of a
+    // symmetric distribution land on the same timestep as they do with scipy.
+    ((low + high) / 2.0 * 1e12).round() / 1e12
+}
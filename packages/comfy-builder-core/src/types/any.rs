@@ -0,0 +1,60 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyTypeMethods;
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+
+/// Wildcard value accepting anything connected to the socket, kept as the raw Python object.
+#[derive(Debug)]
+pub struct Any {
+    object: Py<PyAny>,
+}
+
+impl Any {
+    pub fn new(object: Py<PyAny>) -> Self {
+        Self { object }
+    }
+
+    pub fn bind<'py>(&self, python: Python<'py>) -> &Bound<'py, PyAny> {
+        self.object.bind(python)
+    }
+
+    pub fn clone_ref(&self, python: Python) -> Self {
+        Self::new(self.object.clone_ref(python))
+    }
+
+    pub fn into_inner(self) -> Py<PyAny> {
+        self.object
+    }
+
+    /// Try to decode the value as one of the known types, e.g. `any.extract::<Image<f32>>(python)`.
+    pub fn extract<'py, T: FromPyObject<'py>>(&self, python: Python<'py>) -> PyResult<T> {
+        self.object.bind(python).extract::<T>()
+    }
+
+    /// Name of the Python type currently held, useful for debugging and switching on the value.
+    pub fn type_name(&self, python: Python) -> PyResult<String> {
+        Ok(self.object.bind(python).get_type().name()?.to_string())
+    }
+}
+
+impl<'py> FromPyObject<'py> for Any {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::new(object.clone().unbind()))
+    }
+}
+
+impl<'py> AsInput<'py> for Any {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Any
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Any {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.object.into_bound(python))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::{Image, torch_to_candle};
+use candle_core::{Device, WithDType};
+use numpy::Element;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::time::Duration;
+
+/// ComfyUI `AUDIO`, a `waveform` tensor shaped `[B, C, T]` and its `sample_rate`.
+#[derive(Clone, Debug)]
+pub struct Audio<T: Element + WithDType = f32> {
+    waveform: Image<T>,
+    sample_rate: u32,
+}
+
+impl<'py, T: Element + WithDType> FromPyObject<'py> for Audio<T> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Audio::new(object.extract::<Bound<'py, PyAny>>()?)
+    }
+}
+
+impl<'py, T: Element + WithDType> AsInput<'py> for Audio<T> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Audio
+    }
+}
+
+impl<T: Element + WithDType> Audio<T> {
+    pub fn new(any: Bound<PyAny>) -> PyResult<Self> {
+        let dict = any.downcast::<PyDict>()?;
+
+        let waveform = dict
+            .get_item("waveform")
+            .map(|waveform| torch_to_candle::<T>(waveform, &Device::Cpu))??;
+        let waveform = Image::from_tensor(waveform);
+
+        let sample_rate = dict.get_item("sample_rate")?.extract::<u32>()?;
+
+        Self::from_waveform(waveform, sample_rate)
+            .map_err(|error| PyValueError::new_err(format!("invalid audio: {}", error)))
+    }
+
+    pub fn from_waveform(waveform: Image<T>, sample_rate: u32) -> candle_core::Result<Self> {
+        waveform.dims3()?;
+
+        Ok(Self { waveform, sample_rate })
+    }
+
+    pub fn waveform(&self) -> &Image<T> {
+        &self.waveform
+    }
+
+    pub fn into_waveform(self) -> Image<T> {
+        self.waveform
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.waveform.dims()[0]
+    }
+
+    pub fn channels(&self) -> usize {
+        self.waveform.dims()[1]
+    }
+
+    pub fn samples(&self) -> usize {
+        self.waveform.dims()[2]
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self.sample_rate {
+            0 => Duration::ZERO,
+            rate => Duration::from_secs_f64(self.samples() as f64 / rate as f64),
+        }
+    }
+}
+
+impl<'py, T: Element + WithDType> IntoPyObject<'py> for Audio<T> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dic = PyDict::new(py);
+
+        dic.set_item("waveform", self.waveform.into_pyobject(py)?)?;
+        dic.set_item("sample_rate", self.sample_rate)?;
+
+        Ok(dic.into_any())
+    }
+}
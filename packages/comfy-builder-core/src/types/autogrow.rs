@@ -0,0 +1,127 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::prelude::{PyAnyMethods, PyDictMethods};
+use pyo3::types::{PyDict, PyList};
+use pyo3::{Bound, FromPyObject, PyAny, PyResult};
+use std::ops::Deref;
+
+const DEFAULT_MIN: usize = 1;
+const DEFAULT_MAX: usize = 10;
+
+/// Group of sockets that grows a new slot every time the last one gets connected.
+///
+/// Decodes into the connected values in slot order, without switching the node into list mode.
+/// `#[min = ...]` and `#[max = ...]` on the field bound the number of slots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autogrow<T> {
+    values: Vec<T>,
+}
+
+impl<T> Autogrow<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        Self { values }
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T> Deref for Autogrow<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<T> From<Vec<T>> for Autogrow<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self::new(values)
+    }
+}
+
+impl<T> IntoIterator for Autogrow<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// Slot order of keys such as `image0`, `image1`, ..., `image10`.
+fn slot_index(key: &str) -> (usize, usize) {
+    let prefix = key.trim_end_matches(|char: char| char.is_ascii_digit());
+    let index = key[prefix.len()..].parse().unwrap_or_default();
+
+    (prefix.len(), index)
+}
+
+impl<'py, T: FromPyObject<'py>> FromPyObject<'py> for Autogrow<T> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(list) = object.downcast::<PyList>() {
+            return Ok(Self::new(list.extract()?));
+        }
+
+        let mut slots = object
+            .downcast::<PyDict>()?
+            .iter()
+            .filter(|(_, value)| !value.is_none())
+            .map(|(key, value)| Ok((key.extract::<String>()?, value)))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        slots.sort_by_key(|(key, _)| slot_index(key));
+
+        let values = slots
+            .into_iter()
+            .map(|(_, value)| value.extract::<T>())
+            .collect::<PyResult<_>>()?;
+
+        Ok(Self::new(values))
+    }
+}
+
+impl<'py, T: AsInput<'py>> AsInput<'py> for Autogrow<T> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Autogrow
+    }
+
+    fn set_options(dict: &mut Bound<'py, PyDict>, io: &Bound<'py, PyAny>) -> PyResult<()> {
+        let python = dict.py();
+        let comfy_type = T::comfy_type();
+        let prefix = comfy_type.to_string().to_lowercase();
+
+        let mut options = PyDict::new(python);
+
+        T::set_options(&mut options, io)?;
+
+        let template_input = comfy_type
+            .io_class(io)?
+            .getattr("Input")?
+            .call((&prefix,), Some(&options))?;
+
+        let kwargs = PyDict::new(python);
+
+        kwargs.set_item("prefix", prefix)?;
+        kwargs.set_item("min", take_bound(dict, "min", DEFAULT_MIN)?)?;
+        kwargs.set_item("max", take_bound(dict, "max", DEFAULT_MAX)?)?;
+
+        let template = io
+            .getattr("Autogrow")?
+            .getattr("TemplatePrefix")?
+            .call((template_input,), Some(&kwargs))?;
+
+        dict.set_item("template", template)
+    }
+}
+
+/// `Autogrow.Input` does not accept `min` / `max`, they belong to the template instead.
+fn take_bound(dict: &Bound<PyDict>, key: &str, default: usize) -> PyResult<usize> {
+    match dict.get_item(key)? {
+        Some(value) => {
+            dict.del_item(key)?;
+            value.extract()
+        }
+        None => Ok(default),
+    }
+}
@@ -0,0 +1,133 @@
+use crate::types::color::Color;
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::Image;
+use crate::types::point::Point;
+use candle_core::{D, WithDType};
+use numpy::Element;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+
+/// Pixel region exchanged as a `{"x": ..., "y": ..., "width": ..., "height": ...}` dict.
+///
+/// `BOUNDING_BOX` is a link-only socket: ComfyUI has no widget for it, so it has to be connected
+/// to the output of another node. Edges past `usize::MAX` saturate instead of overflowing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BoundingBox {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl BoundingBox {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Smallest box containing both corners.
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        Self::new(a.x.min(b.x), a.y.min(b.y), a.x.abs_diff(b.x), a.y.abs_diff(b.y))
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn bottom_right(&self) -> Point {
+        Point::new(self.x.saturating_add(self.width), self.y.saturating_add(self.height))
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let bottom_right = self.bottom_right();
+
+        (self.x..bottom_right.x).contains(&point.x) && (self.y..bottom_right.y).contains(&point.y)
+    }
+
+    /// The part of the box that lies within an image of the given size.
+    pub fn clamp(&self, width: usize, height: usize) -> Self {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+
+        Self::new(x, y, self.width.min(width - x), self.height.min(height - y))
+    }
+}
+
+impl<'py> FromPyObject<'py> for BoundingBox {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok((x, y, width, height)) = object.extract::<(usize, usize, usize, usize)>() {
+            return Ok(Self::new(x, y, width, height));
+        }
+
+        Ok(Self::new(
+            object.get_item("x")?.extract()?,
+            object.get_item("y")?.extract()?,
+            object.get_item("width")?.extract()?,
+            object.get_item("height")?.extract()?,
+        ))
+    }
+}
+
+impl<'py> AsInput<'py> for BoundingBox {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Custom("BOUNDING_BOX")
+    }
+}
+
+impl<'py> IntoPyObject<'py> for BoundingBox {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dict = PyDict::new(python);
+
+        dict.set_item("x", self.x)?;
+        dict.set_item("y", self.y)?;
+        dict.set_item("width", self.width)?;
+        dict.set_item("height", self.height)?;
+
+        Ok(dict.into_any())
+    }
+}
+
+impl<T: Element + WithDType> Image<T> {
+    fn clamp_box(&self, bounding_box: &BoundingBox) -> candle_core::Result<BoundingBox> {
+        let (_, height, width, _) = self.dims4()?;
+
+        Ok(bounding_box.clamp(width, height))
+    }
+
+    /// Crop every image of the `[B, H, W, C]` batch to the box, clamped to the image bounds.
+    pub fn crop(&self, bounding_box: &BoundingBox) -> candle_core::Result<Image<T>> {
+        let region = self.clamp_box(bounding_box)?;
+        let tensor = self
+            .narrow(1, region.y, region.height)?
+            .narrow(2, region.x, region.width)?;
+
+        Ok(Image::from_tensor(tensor.contiguous()?))
+    }
+
+    /// Paint the box with `color` on every image of the batch.
+    pub fn fill_box(&self, bounding_box: &BoundingBox, color: Color) -> candle_core::Result<Image<T>> {
+        let region = self.clamp_box(bounding_box)?;
+        let bottom_right = region.bottom_right();
+        let channels = self.dim(D::Minus1)?;
+        let patch = self
+            .color_like(color)?
+            .narrow(1, region.y, region.height)?
+            .narrow(2, region.x, region.width)?;
+
+        let tensor = self.slice_assign(
+            &[
+                0..self.dims()[0],
+                region.y..bottom_right.y,
+                region.x..bottom_right.x,
+                0..channels,
+            ],
+            &patch.contiguous()?,
+        )?;
+
+        Ok(Image::from_tensor(tensor))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::Image;
+use candle_core::{D, Tensor as CandleTensor, WithDType};
+use numpy::Element;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// RGBA color exchanged with the color picker widget as a `#rrggbb` / `#rrggbbaa` hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Parse `#rgb`, `#rrggbb` or `#rrggbbaa`, the leading `#` being optional.
+    pub fn from_hex(hex: &str) -> PyResult<Self> {
+        let digits = hex.trim().trim_start_matches('#');
+        let invalid = || PyValueError::new_err(format!("invalid hex color: {}", hex));
+
+        let channel = |index: usize| {
+            digits
+                .get(index..index + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        };
+
+        match digits.len() {
+            3 => {
+                let expanded: String = digits.chars().flat_map(|char| [char, char]).collect();
+
+                Self::from_hex(&expanded)
+            }
+            6 => Ok(Self::rgb(channel(0)?, channel(2)?, channel(4)?)),
+            8 => Ok(Self::rgba(channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// `#rrggbb` when fully opaque, `#rrggbbaa` otherwise.
+    pub fn to_hex(&self) -> String {
+        match self.a {
+            255 => format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b),
+            _ => format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a),
+        }
+    }
+
+    /// Channels scaled to the `0.0..=1.0` range used by ComfyUI image tensors.
+    pub fn to_normalized(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a].map(|channel| channel as f32 / 255.0)
+    }
+
+    fn channels(&self, count: usize) -> candle_core::Result<Vec<f32>> {
+        let [r, g, b, a] = self.to_normalized();
+
+        match count {
+            1 => Ok(vec![0.299 * r + 0.587 * g + 0.114 * b]),
+            3 => Ok(vec![r, g, b]),
+            4 => Ok(vec![r, g, b, a]),
+            _ => candle_core::bail!("can not fill an image with {} channels with a color", count),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = PyErr;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(hex)
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}", self.to_hex())
+    }
+}
+
+impl<'py> FromPyObject<'py> for Color {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(value) = object.extract::<u32>() {
+            let [_, r, g, b] = value.to_be_bytes();
+
+            return Ok(Self::rgb(r, g, b));
+        }
+
+        Self::from_hex(&object.extract::<String>()?)
+    }
+}
+
+impl<'py> AsInput<'py> for Color {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Color
+    }
+
+    fn set_options(dict: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
+        if dict.get_item("default").is_err() {
+            dict.set_item("default", Color::WHITE.to_hex())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Color {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.to_hex().into_pyobject(python)?.into_any())
+    }
+}
+
+impl<T: Element + WithDType> Image<T> {
+    /// Tensor of the image's shape `[B, H, W, C]` where every pixel is `color`.
+    pub(crate) fn color_like(&self, color: Color) -> candle_core::Result<CandleTensor> {
+        let channels = color.channels(self.dim(D::Minus1)?)?;
+
+        CandleTensor::new(channels, self.device())?
+            .to_dtype(self.dtype())?
+            .broadcast_as(self.shape())
+    }
+
+    /// New image of the same size, filled with `color`.
+    pub fn fill(&self, color: Color) -> candle_core::Result<Image<T>> {
+        Ok(Image::from_tensor(self.color_like(color)?.contiguous()?))
+    }
+}
@@ -1,3 +1,4 @@
+use pyo3::prelude::PyAnyMethods;
 use pyo3::types::PyDict;
 use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyResult};
 use std::fmt::{Display, Formatter};
@@ -8,11 +9,15 @@ pub trait AsInput<'py>: FromPyObject<'py> {
     fn set_options(_: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
         Ok(())
     }
+
+    fn set_output_options(_: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
+        Ok(())
+    }
 }
 
 pub trait AsOutput<'py>: IntoPyObject<'py> {}
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ComfyType {
     Int,
     Float,
@@ -22,8 +27,36 @@ pub enum ComfyType {
     Mask,
     Latent,
     Enum,
-    Slider,
     Sigmas,
+    Model,
+    Clip,
+    Vae,
+    Conditioning,
+    Audio,
+    Video,
+    Any,
+    Match,
+    MultiCombo,
+    Autogrow,
+    Color,
+    Sampler,
+    Guider,
+    ControlNet,
+    ClipVision,
+    UpscaleModel,
+    StyleModel,
+    Gligen,
+    Custom(&'static str),
+}
+
+impl ComfyType {
+    /// Resolve the `io` class used to declare inputs and outputs of this type.
+    pub fn io_class<'py>(&self, io: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            ComfyType::Custom(name) => io.getattr("Custom")?.call1((*name,)),
+            _ => io.getattr(self.to_string()),
+        }
+    }
 }
 
 impl Display for ComfyType {
@@ -41,8 +74,25 @@ impl Display for ComfyType {
                 ComfyType::Boolean => "Boolean".to_string(),
                 ComfyType::Enum => "Combo".to_string(),
                 ComfyType::Sigmas => "Sigmas".to_string(),
-                // Custom
-                ComfyType::Slider => "Int".to_string(),
+                ComfyType::Model => "Model".to_string(),
+                ComfyType::Clip => "Clip".to_string(),
+                ComfyType::Vae => "Vae".to_string(),
+                ComfyType::Conditioning => "Conditioning".to_string(),
+                ComfyType::Audio => "Audio".to_string(),
+                ComfyType::Video => "Video".to_string(),
+                ComfyType::Any => "AnyType".to_string(),
+                ComfyType::Match => "MatchType".to_string(),
+                ComfyType::MultiCombo => "MultiCombo".to_string(),
+                ComfyType::Autogrow => "Autogrow".to_string(),
+                ComfyType::Color => "Color".to_string(),
+                ComfyType::Sampler => "Sampler".to_string(),
+                ComfyType::Guider => "Guider".to_string(),
+                ComfyType::ControlNet => "ControlNet".to_string(),
+                ComfyType::ClipVision => "ClipVision".to_string(),
+                ComfyType::UpscaleModel => "UpscaleModel".to_string(),
+                ComfyType::StyleModel => "StyleModel".to_string(),
+                ComfyType::Gligen => "Gligen".to_string(),
+                ComfyType::Custom(name) => name.to_string(),
             }
         )
     }
@@ -0,0 +1,276 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::{tensor_to_pytensor, torch_to_candle};
+use candle_core::{Device, Tensor as CandleTensor, WithDType};
+use numpy::Element;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods, PyListMethods};
+use pyo3::types::{PyDict, PyList, PyTuple};
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+use std::marker::PhantomData;
+
+/// ComfyUI `CONDITIONING`, a list of `[embeddings, metadata]` pairs.
+#[derive(Debug)]
+pub struct Conditioning<T: Element + WithDType = f32> {
+    entries: Vec<ConditioningEntry<T>>,
+}
+
+#[derive(Debug)]
+pub struct ConditioningEntry<T: Element + WithDType = f32> {
+    pub embeddings: CandleTensor,
+    pub metadata: ConditioningMetadata,
+    marker: PhantomData<T>,
+}
+
+/// Metadata attached to a conditioning entry.
+///
+/// Keys that are not mapped to a typed field (or hold `None`) are kept in `extra`
+/// and written back untouched, so that values set by other nodes survive a round-trip.
+#[derive(Debug, Default)]
+pub struct ConditioningMetadata {
+    pub pooled_output: Option<CandleTensor>,
+    pub area: Option<ConditioningArea>,
+    pub strength: Option<f64>,
+    pub mask: Option<CandleTensor>,
+    pub mask_strength: Option<f64>,
+    pub set_area_to_bounds: Option<bool>,
+    pub start_percent: Option<f64>,
+    pub end_percent: Option<f64>,
+    pub extra: Vec<(String, Py<PyAny>)>,
+}
+
+/// The `area` key, either in latent units `(height, width, y, x)` or as `("percentage", height, width, y, x)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConditioningArea {
+    Latent {
+        height: usize,
+        width: usize,
+        y: usize,
+        x: usize,
+    },
+    Percentage {
+        height: f64,
+        width: f64,
+        y: f64,
+        x: f64,
+    },
+}
+
+impl<T: Element + WithDType> Conditioning<T> {
+    pub fn new(entries: Vec<ConditioningEntry<T>>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[ConditioningEntry<T>] {
+        &self.entries
+    }
+
+    pub fn entries_mut(&mut self) -> &mut Vec<ConditioningEntry<T>> {
+        &mut self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<ConditioningEntry<T>> {
+        self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Element + WithDType> ConditioningEntry<T> {
+    pub fn new(embeddings: CandleTensor, metadata: ConditioningMetadata) -> Self {
+        Self {
+            embeddings,
+            metadata,
+            marker: PhantomData,
+        }
+    }
+
+    fn decode(any: &Bound<PyAny>) -> PyResult<Self> {
+        // ComfyUI builds the pairs as lists, though tuples show up as well.
+        let embeddings = any.get_item(0)?;
+        let metadata = any.get_item(1)?;
+
+        Ok(Self::new(
+            torch_to_candle::<T>(embeddings, &Device::Cpu)?,
+            ConditioningMetadata::decode::<T>(metadata.downcast()?)?,
+        ))
+    }
+
+    fn encode<'py>(self, python: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        PyList::new(
+            python,
+            [
+                tensor_to_pytensor::<T>(python, self.embeddings)?,
+                self.metadata.encode::<T>(python)?.into_any(),
+            ],
+        )
+    }
+}
+
+impl<T: Element + WithDType> From<CandleTensor> for ConditioningEntry<T> {
+    fn from(embeddings: CandleTensor) -> Self {
+        Self::new(embeddings, ConditioningMetadata::default())
+    }
+}
+
+impl ConditioningMetadata {
+    fn decode<T: Element + WithDType>(dict: &Bound<PyDict>) -> PyResult<Self> {
+        let mut metadata = Self::default();
+
+        for (key, value) in dict.iter() {
+            let key = key.extract::<String>()?;
+
+            if value.is_none() {
+                metadata.extra.push((key, value.unbind()));
+                continue;
+            }
+
+            let invalid = |error: PyErr| PyValueError::new_err(format!("invalid conditioning `{}`: {}", key, error));
+
+            match key.as_str() {
+                "pooled_output" => {
+                    metadata.pooled_output = Some(torch_to_candle::<T>(value, &Device::Cpu).map_err(invalid)?)
+                }
+                "mask" => metadata.mask = Some(torch_to_candle::<T>(value, &Device::Cpu).map_err(invalid)?),
+                "area" => metadata.area = Some(ConditioningArea::decode(&value).map_err(invalid)?),
+                "strength" => metadata.strength = Some(value.extract().map_err(invalid)?),
+                "mask_strength" => metadata.mask_strength = Some(value.extract().map_err(invalid)?),
+                "set_area_to_bounds" => metadata.set_area_to_bounds = Some(value.extract().map_err(invalid)?),
+                "start_percent" => metadata.start_percent = Some(value.extract().map_err(invalid)?),
+                "end_percent" => metadata.end_percent = Some(value.extract().map_err(invalid)?),
+                _ => metadata.extra.push((key, value.unbind())),
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    fn encode<'py, T: Element + WithDType>(self, python: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(python);
+
+        if let Some(pooled_output) = self.pooled_output {
+            dict.set_item("pooled_output", tensor_to_pytensor::<T>(python, pooled_output)?)?;
+        }
+
+        if let Some(area) = self.area {
+            dict.set_item("area", area.encode(python)?)?;
+        }
+
+        if let Some(strength) = self.strength {
+            dict.set_item("strength", strength)?;
+        }
+
+        if let Some(mask) = self.mask {
+            dict.set_item("mask", tensor_to_pytensor::<T>(python, mask)?)?;
+        }
+
+        if let Some(mask_strength) = self.mask_strength {
+            dict.set_item("mask_strength", mask_strength)?;
+        }
+
+        if let Some(set_area_to_bounds) = self.set_area_to_bounds {
+            dict.set_item("set_area_to_bounds", set_area_to_bounds)?;
+        }
+
+        if let Some(start_percent) = self.start_percent {
+            dict.set_item("start_percent", start_percent)?;
+        }
+
+        if let Some(end_percent) = self.end_percent {
+            dict.set_item("end_percent", end_percent)?;
+        }
+
+        for (key, value) in self.extra {
+            dict.set_item(key, value)?;
+        }
+
+        Ok(dict)
+    }
+
+    pub fn clone_ref(&self, python: Python) -> Self {
+        Self {
+            pooled_output: self.pooled_output.clone(),
+            area: self.area,
+            strength: self.strength,
+            mask: self.mask.clone(),
+            mask_strength: self.mask_strength,
+            set_area_to_bounds: self.set_area_to_bounds,
+            start_percent: self.start_percent,
+            end_percent: self.end_percent,
+            extra: self
+                .extra
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone_ref(python)))
+                .collect(),
+        }
+    }
+}
+
+impl ConditioningArea {
+    fn decode(any: &Bound<PyAny>) -> PyResult<Self> {
+        if let Ok((kind, height, width, y, x)) = any.extract::<(String, f64, f64, f64, f64)>() {
+            return match kind.as_str() {
+                "percentage" => Ok(ConditioningArea::Percentage { height, width, y, x }),
+                _ => Err(PyValueError::new_err(format!("unknown area kind: {}", kind))),
+            };
+        }
+
+        let (height, width, y, x) = any.extract::<(usize, usize, usize, usize)>()?;
+
+        Ok(ConditioningArea::Latent { height, width, y, x })
+    }
+
+    fn encode<'py>(self, python: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        match self {
+            ConditioningArea::Latent { height, width, y, x } => (height, width, y, x).into_pyobject(python),
+            ConditioningArea::Percentage { height, width, y, x } => {
+                ("percentage", height, width, y, x).into_pyobject(python)
+            }
+        }
+    }
+}
+
+impl<'py, T: Element + WithDType> FromPyObject<'py> for Conditioning<T> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let entries = object
+            .downcast::<PyList>()?
+            .iter()
+            .map(|entry| ConditioningEntry::decode(&entry))
+            .collect::<PyResult<_>>()?;
+
+        Ok(Self::new(entries))
+    }
+}
+
+impl<'py, T: Element + WithDType> AsInput<'py> for Conditioning<T> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Conditioning
+    }
+}
+
+impl<'py, T: Element + WithDType> IntoPyObject<'py> for Conditioning<T> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let list = PyList::empty(python);
+
+        for entry in self.entries {
+            list.append(entry.encode(python)?)?;
+        }
+
+        Ok(list.into_any())
+    }
+}
+
+impl<T: Element + WithDType> From<Vec<ConditioningEntry<T>>> for Conditioning<T> {
+    fn from(entries: Vec<ConditioningEntry<T>>) -> Self {
+        Self::new(entries)
+    }
+}
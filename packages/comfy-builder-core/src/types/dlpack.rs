@@ -0,0 +1,290 @@
+//! Exchange of tensors with torch through [DLPack](https://dmlc.github.io/dlpack/latest/).
+//!
+//! Memory is never shared between candle and torch, and each direction makes exactly one copy
+//! of a contiguous cpu tensor. Candle tensors handed to torch are copied straight from their
+//! storage into a buffer owned by the capsule, as that storage may be shared with other candle
+//! tensors, which expect it to never change. Torch tensors are copied straight from their memory
+//! into the storage of a new candle tensor.
+
+use candle_core::{CpuStorage, DType, Device, Layout, Shape, Storage, Tensor as CandleTensor};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::ffi;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::{PyCapsule, PyCapsuleMethods};
+use pyo3::{Bound, PyAny, PyErr, PyResult, Python};
+use std::ffi::{CStr, c_void};
+use std::ptr;
+
+const CAPSULE_NAME: &CStr = c"dltensor";
+
+const CPU: i32 = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DLDevice {
+    device_type: i32,
+    device_id: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct DLDataType {
+    code: u8,
+    bits: u8,
+    lanes: u16,
+}
+
+#[repr(C)]
+struct DLTensor {
+    data: *mut c_void,
+    device: DLDevice,
+    ndim: i32,
+    dtype: DLDataType,
+    shape: *mut i64,
+    strides: *mut i64,
+    byte_offset: u64,
+}
+
+#[repr(C)]
+struct DLManagedTensor {
+    dl_tensor: DLTensor,
+    manager_ctx: *mut c_void,
+    deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+/// What a capsule created by [`to_dlpack`] points to, `managed` has to stay the first field.
+#[repr(C)]
+struct SharedTensor {
+    managed: DLManagedTensor,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+    data: Vec<u64>,
+}
+
+impl DLDataType {
+    fn from_candle(dtype: DType) -> Self {
+        let code = match dtype {
+            DType::U8 | DType::U32 => 1,
+            DType::I64 => 0,
+            DType::BF16 => 4,
+            DType::F16 | DType::F32 | DType::F64 => 2,
+        };
+
+        Self {
+            code,
+            bits: (dtype.size_in_bytes() * 8) as u8,
+            lanes: 1,
+        }
+    }
+
+    fn to_candle(self) -> Option<DType> {
+        [
+            DType::U8,
+            DType::U32,
+            DType::I64,
+            DType::BF16,
+            DType::F16,
+            DType::F32,
+            DType::F64,
+        ]
+        .into_iter()
+        .find(|dtype| DLDataType::from_candle(*dtype) == self)
+    }
+}
+
+fn candle_error(error: candle_core::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("Execution failed: {}", error))
+}
+
+unsafe extern "C" fn delete_shared(managed: *mut DLManagedTensor) {
+    // SAFETY: `deleter` is only set by `to_dlpack`, on a `SharedTensor` it leaked from a box.
+    drop(unsafe { Box::from_raw(managed.cast::<SharedTensor>()) });
+}
+
+unsafe extern "C" fn delete_capsule(capsule: *mut ffi::PyObject) {
+    // Once a consumer takes over the tensor it renames the capsule and becomes responsible for it.
+    // SAFETY: this is the destructor of a capsule, which is still valid while it runs.
+    unsafe {
+        if ffi::PyCapsule_IsValid(capsule, CAPSULE_NAME.as_ptr()) == 1 {
+            let managed = ffi::PyCapsule_GetPointer(capsule, CAPSULE_NAME.as_ptr()).cast::<DLManagedTensor>();
+
+            if let Some(deleter) = (*managed).deleter {
+                deleter(managed)
+            }
+        }
+    }
+}
+
+fn as_bytes<T>(values: &[T]) -> &[u8] {
+    // SAFETY: the values are plain numbers, any of their bytes can be read as `u8`.
+    unsafe { std::slice::from_raw_parts(values.as_ptr().cast(), size_of_val(values)) }
+}
+
+/// Copy the values of `tensor` into a buffer aligned for every dtype candle supports, in a single
+/// pass over its storage. Tensors that are not contiguous or not on the cpu are made so first.
+fn aligned_copy(tensor: &CandleTensor) -> candle_core::Result<Vec<u64>> {
+    let tensor = match tensor.device().is_cpu() && tensor.is_contiguous() {
+        true => tensor.clone(),
+        false => tensor.to_device(&Device::Cpu)?.contiguous()?,
+    };
+
+    let (storage, layout) = tensor.storage_and_layout();
+
+    let (Storage::Cpu(storage), Some((start, end))) = (&*storage, layout.contiguous_offsets()) else {
+        candle_core::bail!("expected a contiguous tensor on the cpu")
+    };
+
+    let bytes = match storage {
+        CpuStorage::U8(data) => as_bytes(&data[start..end]),
+        CpuStorage::U32(data) => as_bytes(&data[start..end]),
+        CpuStorage::I64(data) => as_bytes(&data[start..end]),
+        CpuStorage::BF16(data) => as_bytes(&data[start..end]),
+        CpuStorage::F16(data) => as_bytes(&data[start..end]),
+        CpuStorage::F32(data) => as_bytes(&data[start..end]),
+        CpuStorage::F64(data) => as_bytes(&data[start..end]),
+    };
+
+    let mut buffer = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+
+    // SAFETY: `buffer` holds at least `bytes.len()` bytes and is a new allocation, so they don't overlap.
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr().cast::<u8>(), bytes.len()) };
+
+    Ok(buffer)
+}
+
+/// Copy a candle tensor into a new buffer and wrap it into a DLPack capsule.
+///
+/// Contiguous cpu tensors are copied exactly once, straight from their storage. The capsule owns
+/// the copy, so the consumer is free to change it in place, e.g. through
+/// `torch.from_dlpack(capsule).mul_(2)`, without affecting `tensor`.
+pub fn to_dlpack<'py>(python: Python<'py>, tensor: &CandleTensor) -> PyResult<Bound<'py, PyCapsule>> {
+    let data = aligned_copy(tensor).map_err(candle_error)?;
+
+    let mut shared = Box::new(SharedTensor {
+        managed: DLManagedTensor {
+            dl_tensor: DLTensor {
+                data: ptr::null_mut(),
+                device: DLDevice {
+                    device_type: CPU,
+                    device_id: 0,
+                },
+                ndim: tensor.rank() as i32,
+                dtype: DLDataType::from_candle(tensor.dtype()),
+                shape: ptr::null_mut(),
+                strides: ptr::null_mut(),
+                byte_offset: 0,
+            },
+            manager_ctx: ptr::null_mut(),
+            deleter: Some(delete_shared),
+        },
+        shape: tensor.dims().iter().map(|dim| *dim as i64).collect(),
+        strides: Layout::contiguous(tensor.shape())
+            .stride()
+            .iter()
+            .map(|stride| *stride as i64)
+            .collect(),
+        data,
+    });
+
+    shared.managed.dl_tensor.data = shared.data.as_mut_ptr().cast();
+    shared.managed.dl_tensor.shape = shared.shape.as_mut_ptr();
+    shared.managed.dl_tensor.strides = shared.strides.as_mut_ptr();
+
+    let shared = Box::into_raw(shared);
+
+    // SAFETY: the capsule takes over `shared`, which is released by `delete_capsule` or by
+    // whoever consumes the capsule, and `CAPSULE_NAME` outlives it.
+    unsafe {
+        let capsule = ffi::PyCapsule_New(shared.cast(), CAPSULE_NAME.as_ptr(), Some(delete_capsule));
+
+        if capsule.is_null() {
+            delete_shared(shared.cast());
+        }
+
+        Ok(Bound::from_owned_ptr_or_err(python, capsule)?.downcast_into_unchecked())
+    }
+}
+
+/// Copy a tensor out of a DLPack capsule, or out of any object implementing `__dlpack__`.
+///
+/// Returns `None` for tensors that can not be read this way, such as tensors that are not on the
+/// cpu, are not contiguous or have a dtype candle does not support, leaving them to the caller.
+pub fn from_dlpack(object: &Bound<PyAny>, device: &Device) -> PyResult<Option<CandleTensor>> {
+    let capsule = match object.downcast::<PyCapsule>() {
+        Ok(capsule) => capsule.clone(),
+        Err(_) if object.hasattr("__dlpack__")? => {
+            let (device_type, _) = object.call_method0("__dlpack_device__")?.extract::<(i32, i32)>()?;
+
+            if device_type != CPU {
+                return Ok(None);
+            }
+
+            object.call_method0("__dlpack__")?.downcast_into::<PyCapsule>()?
+        }
+        Err(_) => return Ok(None),
+    };
+
+    if capsule.name()? != Some(CAPSULE_NAME) {
+        return Err(PyRuntimeError::new_err("the DLPack capsule has already been consumed"));
+    }
+
+    // SAFETY: capsules named `dltensor` carry a `DLManagedTensor`, which the capsule keeps alive
+    // while it is being copied from, as it is not consumed here.
+    let tensor = unsafe { &(*capsule.pointer().cast::<DLManagedTensor>()).dl_tensor };
+
+    let Some(dtype) = tensor.dtype.to_candle() else {
+        return Ok(None);
+    };
+
+    if tensor.device.device_type != CPU {
+        return Ok(None);
+    }
+
+    let rank = tensor.ndim as usize;
+
+    // SAFETY: `shape` and `strides`, unless null, hold `ndim` entries.
+    let (dims, strides) = unsafe {
+        let dims = match rank {
+            0 => &[][..],
+            _ => std::slice::from_raw_parts(tensor.shape, rank),
+        };
+
+        let strides = match tensor.strides.is_null() || rank == 0 {
+            true => None,
+            false => Some(std::slice::from_raw_parts(tensor.strides, rank)),
+        };
+
+        (dims, strides)
+    };
+
+    let shape = Shape::from(dims.iter().map(|dim| *dim as usize).collect::<Vec<_>>());
+
+    if let Some(strides) = strides {
+        let strides = strides
+            .iter()
+            .map(|stride| usize::try_from(*stride).ok())
+            .collect::<Option<Vec<_>>>();
+
+        if !strides.is_some_and(|strides| shape.is_contiguous(&strides)) {
+            return Ok(None);
+        }
+    }
+
+    if shape.elem_count() == 0 {
+        return CandleTensor::zeros(shape, dtype, device)
+            .map(Some)
+            .map_err(candle_error);
+    }
+
+    // SAFETY: a contiguous tensor covers `elem_count` values from `data` plus `byte_offset`.
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            tensor.data.cast::<u8>().add(tensor.byte_offset as usize),
+            shape.elem_count() * dtype.size_in_bytes(),
+        )
+    };
+
+    CandleTensor::from_raw_buffer(bytes, dtype, shape.dims(), device)
+        .map(Some)
+        .map_err(candle_error)
+}
@@ -0,0 +1,26 @@
+use pyo3::PyResult;
+use pyo3::exceptions::PyValueError;
+
+/// Combo options computed at runtime, such as files in a directory or detected devices.
+///
+/// Implement it on a `String` newtype and add `#[derive(DynamicOptions)]` to turn it into a combo input.
+/// The options are listed again every time the schema is defined, and the selected value is checked
+/// against a fresh list when the node executes.
+pub trait DynamicOptions {
+    fn options() -> Vec<String>;
+}
+
+/// Ensure `value` is still one of the options currently offered by `T`.
+pub fn validate_option<T: DynamicOptions>(value: String) -> PyResult<String> {
+    let options = T::options();
+
+    if options.contains(&value) {
+        return Ok(value);
+    }
+
+    Err(PyValueError::new_err(format!(
+        "`{}` is not one of the available options: [{}]",
+        value,
+        options.join(", ")
+    )))
+}
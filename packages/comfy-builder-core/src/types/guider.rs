@@ -0,0 +1,193 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::conditioning::Conditioning;
+use crate::types::handle::Model;
+use crate::types::sampler::{SamplingError, to_candle, to_torch};
+use candle_core::Tensor;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods, PyListMethods};
+use pyo3::types::{PyCFunction, PyDict, PyList, PyTuple};
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+use std::sync::Arc;
+
+/// The model as seen from inside a guider, able to run any of the attached conditionings.
+pub trait ConditionedModel {
+    /// Predict the denoised sample from `x` once for every conditioning in `conds`, in order.
+    fn predict(&self, x: &Tensor, conds: &[&str]) -> Result<Vec<Tensor>, SamplingError>;
+}
+
+/// Guidance logic implemented in Rust, equivalent to overriding `predict_noise` on a ComfyUI guider.
+pub trait GuiderFunction: Send + Sync + 'static {
+    /// Combine the model predictions for `x` at noise level `sigma` into the final denoised sample.
+    fn predict(&self, model: &dyn ConditionedModel, x: &Tensor, sigma: f64) -> Result<Tensor, SamplingError>;
+}
+
+enum GuiderKind {
+    Rust {
+        model: Model,
+        conds: Vec<(String, Conditioning)>,
+        function: Arc<dyn GuiderFunction>,
+    },
+    Python(Py<PyAny>),
+}
+
+/// ComfyUI `GUIDER`, either a Rust [`GuiderFunction`] or a guider received from another node.
+pub struct Guider {
+    kind: GuiderKind,
+}
+
+impl Guider {
+    pub fn new<G: GuiderFunction>(model: Model, guider: G) -> Self {
+        Self {
+            kind: GuiderKind::Rust {
+                model,
+                conds: vec![],
+                function: Arc::new(guider),
+            },
+        }
+    }
+
+    pub fn from_object(object: Py<PyAny>) -> Self {
+        Self {
+            kind: GuiderKind::Python(object),
+        }
+    }
+
+    /// Attach a conditioning under `name`, e.g. `positive` or `negative`, for [`ConditionedModel::predict`].
+    ///
+    /// Has no effect on guiders received from other nodes.
+    pub fn with_conds(mut self, name: impl Into<String>, conditioning: Conditioning) -> Self {
+        if let GuiderKind::Rust { conds, .. } = &mut self.kind {
+            conds.push((name.into(), conditioning));
+        }
+
+        self
+    }
+}
+
+struct PythonConditionedModel<'py> {
+    guider: Bound<'py, PyAny>,
+    timestep: Bound<'py, PyAny>,
+    model_options: Bound<'py, PyAny>,
+    like: Bound<'py, PyAny>,
+}
+
+impl<'py> ConditionedModel for PythonConditionedModel<'py> {
+    fn predict(&self, x: &Tensor, conds: &[&str]) -> Result<Vec<Tensor>, SamplingError> {
+        let python = self.guider.py();
+        let registered = self.guider.getattr("conds")?;
+        let selected = PyList::empty(python);
+
+        for name in conds {
+            selected.append(registered.call_method1("get", (*name,))?)?;
+        }
+
+        let predictions = python.import("comfy.samplers")?.getattr("calc_cond_batch")?.call1((
+            self.guider.getattr("inner_model")?,
+            selected,
+            to_torch(python, x.clone(), &self.like)?,
+            &self.timestep,
+            &self.model_options,
+        ))?;
+
+        Ok(predictions
+            .try_iter()?
+            .map(|prediction| to_candle(&prediction?))
+            .collect::<PyResult<Vec<_>>>()?)
+    }
+}
+
+fn argument<'py>(
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+    index: usize,
+    key: &str,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    if let Ok(value) = args.get_item(index) {
+        return Ok(Some(value));
+    }
+
+    Ok(match kwargs {
+        Some(kwargs) => kwargs.get_item(key)?,
+        None => None,
+    })
+}
+
+fn run_guider(
+    function: &dyn GuiderFunction,
+    args: &Bound<PyTuple>,
+    kwargs: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    let python = args.py();
+    let (guider, x, timestep) = (args.get_item(0)?, args.get_item(1)?, args.get_item(2)?);
+
+    let model_options = match argument(args, kwargs, 3, "model_options")? {
+        Some(model_options) => model_options,
+        None => PyDict::new(python).into_any(),
+    };
+
+    let sigma = timestep.get_item(0)?.call_method0("item")?.extract::<f64>()?;
+
+    let model = PythonConditionedModel {
+        guider,
+        timestep,
+        model_options,
+        like: x.clone(),
+    };
+
+    let output = function
+        .predict(&model, &to_candle(&x)?, sigma)
+        .map_err(|error| PyRuntimeError::new_err(format!("Guidance failed: {}", error)))?;
+
+    Ok(to_torch(python, output, &x)?.unbind())
+}
+
+impl<'py> FromPyObject<'py> for Guider {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::from_object(object.clone().unbind()))
+    }
+}
+
+impl<'py> AsInput<'py> for Guider {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Guider
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Guider {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let (model, conds, function) = match self.kind {
+            GuiderKind::Python(object) => return Ok(object.into_bound(python)),
+            GuiderKind::Rust { model, conds, function } => (model, conds, function),
+        };
+
+        let guider = python
+            .import("comfy.samplers")?
+            .getattr("CFGGuider")?
+            .call1((model.into_pyobject(python)?,))?;
+
+        let dict = PyDict::new(python);
+
+        for (name, conditioning) in conds {
+            dict.set_item(name, conditioning.into_pyobject(python)?)?;
+        }
+
+        guider.call_method1("inner_set_conds", (dict,))?;
+
+        let predict = PyCFunction::new_closure(python, Some(c"rust_guider"), None, move |args, kwargs| {
+            run_guider(function.as_ref(), args, kwargs)
+        })?;
+
+        let method = python
+            .import("types")?
+            .getattr("MethodType")?
+            .call1((predict, &guider))?;
+
+        guider.setattr("predict_noise", method)?;
+
+        Ok(guider)
+    }
+}
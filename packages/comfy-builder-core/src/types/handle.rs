@@ -0,0 +1,120 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+/// Marks which socket an opaque [`Handle`] is connected to.
+///
+/// Implement it on an empty enum to declare a handle for any object ComfyUI passes around:
+///
+/// ```
+/// # use comfy_builder_core::prelude::ComfyType;
+/// # use comfy_builder_core::types::handle::{Handle, HandleType};
+/// enum PhotoMakerKind {}
+///
+/// impl HandleType for PhotoMakerKind {
+///     const COMFY_TYPE: ComfyType = ComfyType::Custom("PHOTOMAKER");
+/// }
+///
+/// type PhotoMaker = Handle<PhotoMakerKind>;
+/// ```
+pub trait HandleType: 'static {
+    const COMFY_TYPE: ComfyType;
+}
+
+/// An opaque Python object passed through Rust untouched.
+///
+/// The object is never converted, so it can be routed between nodes or used by calling
+/// its Python methods through [`Handle::bind`].
+pub struct Handle<K: HandleType> {
+    object: Py<PyAny>,
+    kind: PhantomData<K>,
+}
+
+impl<K: HandleType> Handle<K> {
+    pub fn new(object: Py<PyAny>) -> Self {
+        Self {
+            object,
+            kind: PhantomData,
+        }
+    }
+
+    pub fn bind<'py>(&self, python: Python<'py>) -> &Bound<'py, PyAny> {
+        self.object.bind(python)
+    }
+
+    pub fn clone_ref(&self, python: Python) -> Self {
+        Self::new(self.object.clone_ref(python))
+    }
+
+    pub fn into_inner(self) -> Py<PyAny> {
+        self.object
+    }
+}
+
+impl<K: HandleType> Debug for Handle<K> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter
+            .debug_struct("Handle")
+            .field("type", &K::COMFY_TYPE)
+            .field("object", &self.object)
+            .finish()
+    }
+}
+
+impl<'py, K: HandleType> FromPyObject<'py> for Handle<K> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::new(object.clone().unbind()))
+    }
+}
+
+impl<'py, K: HandleType> AsInput<'py> for Handle<K> {
+    fn comfy_type() -> ComfyType {
+        K::COMFY_TYPE
+    }
+}
+
+impl<'py, K: HandleType> IntoPyObject<'py> for Handle<K> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.object.into_bound(python))
+    }
+}
+
+macro_rules! handle_types {
+    ($($(#[$documentation:meta])* $name:ident($kind:ident) => $comfy_type:ident),* $(,)?) => {
+        $(
+            #[derive(Debug)]
+            pub enum $kind {}
+
+            impl HandleType for $kind {
+                const COMFY_TYPE: ComfyType = ComfyType::$comfy_type;
+            }
+
+            $(#[$documentation])*
+            pub type $name = Handle<$kind>;
+        )*
+    };
+}
+
+handle_types!(
+    /// Opaque handle to a ComfyUI `MODEL` object.
+    Model(ModelKind) => Model,
+    /// Opaque handle to a ComfyUI `CLIP` object.
+    Clip(ClipKind) => Clip,
+    /// Opaque handle to a ComfyUI `VAE` object.
+    Vae(VaeKind) => Vae,
+    /// Opaque handle to a ComfyUI `CONTROL_NET` object.
+    ControlNet(ControlNetKind) => ControlNet,
+    /// Opaque handle to a ComfyUI `CLIP_VISION` object.
+    ClipVision(ClipVisionKind) => ClipVision,
+    /// Opaque handle to a ComfyUI `UPSCALE_MODEL` object.
+    UpscaleModel(UpscaleModelKind) => UpscaleModel,
+    /// Opaque handle to a ComfyUI `STYLE_MODEL` object.
+    StyleModel(StyleModelKind) => StyleModel,
+    /// Opaque handle to a ComfyUI `GLIGEN` object.
+    Gligen(GligenKind) => Gligen,
+);
@@ -1,16 +1,24 @@
 use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::tensor_cache;
+use crate::types::torch::{self, DTypePolicy, OutputPlacement, Placement, TorchDType};
 use candle_core::shape::ShapeWithOneHole;
 use candle_core::{Device, Tensor as CandleTensor, Tensor, WithDType};
-use numpy::{Element, PyArray, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
-use pyo3::exceptions::PyRuntimeError;
+use numpy::Element;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::PyAnyMethods;
 use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
 use std::marker::PhantomData;
 use std::ops::Deref;
 
+/// ComfyUI `IMAGE`, a batch of images laid out as `[B, H, W, C]`.
+///
+/// The layout is checked when an image is decoded from ComfyUI or built with
+/// [`Image::try_from_tensor`], so a wrongly shaped tensor is reported from Rust instead of
+/// failing later inside ComfyUI.
 #[derive(Clone, Debug)]
 pub struct Image<T: Element + WithDType> {
     tensor: CandleTensor,
+    placement: Option<Placement>,
     marker: PhantomData<T>,
 }
 
@@ -28,19 +36,93 @@ impl<'py, T: Element + WithDType> AsInput<'py> for Image<T> {
 
 impl<T: Element + WithDType> Image<T> {
     pub fn new(any: Bound<PyAny>, device: &Device) -> PyResult<Self> {
-        Ok(Self {
-            tensor: torch_to_candle::<T>(any, device)?,
-            marker: PhantomData,
-        })
+        let placement = Placement::of(&any)?;
+
+        Self::try_from_tensor(torch_to_candle::<T>(any, device)?)
+            .map(|image| image.with_placement(Some(placement)))
+            .map_err(|error| PyValueError::new_err(format!("invalid image: {}", error)))
     }
 
+    /// Wrap a tensor as is, without checking its layout.
     pub fn from_tensor(tensor: CandleTensor) -> Self {
         Self {
             tensor,
+            placement: None,
             marker: PhantomData,
         }
     }
 
+    /// Wrap a `[B, H, W, C]` tensor with 1 to 4 channels, failing for any other shape.
+    pub fn try_from_tensor(tensor: CandleTensor) -> candle_core::Result<Self> {
+        let (_, _, _, channels) = tensor.dims4()?;
+
+        if !(1..=4).contains(&channels) {
+            candle_core::bail!("expected 1 to 4 channels in the last dimension of {:?}", tensor.shape())
+        }
+
+        Ok(Self::from_tensor(tensor))
+    }
+
+    /// The device and dtype of the tensor this image was decoded from, restored on output when
+    /// the node uses [`OutputPlacement::Source`](crate::types::torch::OutputPlacement::Source).
+    pub fn placement(&self) -> Option<&Placement> {
+        self.placement.as_ref()
+    }
+
+    /// Carry a placement over, e.g. from an input to the output computed from it.
+    pub fn with_placement(self, placement: Option<Placement>) -> Self {
+        Self { placement, ..self }
+    }
+
+    /// Convert a `[B, C, H, W]` tensor, the layout most models work with, into an image.
+    pub fn from_bchw(tensor: CandleTensor) -> candle_core::Result<Self> {
+        tensor.dims4()?;
+
+        Self::try_from_tensor(tensor.permute((0, 2, 3, 1))?.contiguous()?)
+    }
+
+    /// The image as a `[B, C, H, W]` tensor.
+    pub fn to_bchw(&self) -> candle_core::Result<CandleTensor> {
+        self.tensor.permute((0, 3, 1, 2))?.contiguous()
+    }
+
+    /// Join several batches of images of the same size into a single batch, placed like the first.
+    pub fn stack(images: &[Image<T>]) -> candle_core::Result<Self> {
+        let tensors: Vec<&CandleTensor> = images.iter().map(|image| &image.tensor).collect();
+        let placement = images.first().and_then(|image| image.placement.clone());
+
+        Ok(Self::try_from_tensor(CandleTensor::cat(&tensors, 0)?)?.with_placement(placement))
+    }
+
+    /// The size of the batch, failing unless the image is laid out as `[B, H, W, C]`, as images
+    /// wrapped with [`Image::from_tensor`] may hold any tensor.
+    pub fn batch_size(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims4()?.0)
+    }
+
+    pub fn height(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims4()?.1)
+    }
+
+    pub fn width(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims4()?.2)
+    }
+
+    pub fn channels(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims4()?.3)
+    }
+
+    /// Each image of the batch, or each frame of a video, as a batch of one.
+    pub fn frames(&self) -> candle_core::Result<impl Iterator<Item = candle_core::Result<Image<T>>> + '_> {
+        Ok((0..self.batch_size()?).map(|index| {
+            Ok(Self {
+                tensor: self.tensor.narrow(0, index, 1)?,
+                placement: self.placement.clone(),
+                marker: PhantomData,
+            })
+        }))
+    }
+
     pub fn into_tensor(self) -> CandleTensor {
         self.tensor
     }
@@ -56,7 +138,7 @@ impl<'py, T: Element + WithDType> IntoPyObject<'py> for Image<T> {
     type Error = PyErr;
 
     fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
-        tensor_to_pytensor::<T>(python, self.tensor)
+        tensor_to_placed_pytensor::<T>(python, self.tensor, self.placement.as_ref())
     }
 }
 
@@ -82,38 +164,34 @@ impl<T: Element + WithDType> Deref for Image<T> {
     }
 }
 
+/// Copy a torch tensor into candle as `T`.
+///
+/// Tensors are checked against the current [`DTypePolicy`] first, which only lets a tensor of
+/// another dtype through, to be cast, under [`DTypePolicy::Cast`]. Conversions are reused
+/// through the [`tensor_cache`] when it is enabled.
 pub fn torch_to_candle<T: Element + WithDType>(torch_tensor: Bound<PyAny>, device: &Device) -> PyResult<CandleTensor> {
-    let mut numpy = torch_tensor.call_method0("numpy")?;
-
-    let mut array = numpy.downcast::<PyArrayDyn<T>>()?;
-
-    if !array.is_contiguous() {
-        numpy = numpy.call_method0("copy")?;
-        array = numpy.downcast::<PyArrayDyn<T>>()?;
-    }
+    DTypePolicy::current().check(&torch_tensor, TorchDType::from(T::DTYPE))?;
 
-    let shape = array.shape().to_vec();
-    let data = array.to_vec()?;
+    tensor_cache::get_or_convert(&torch_tensor, T::DTYPE, device, || {
+        let (tensor, _) = torch::from_torch(torch_tensor.clone(), device)?;
 
-    CandleTensor::from_vec(data, shape, device)
-        .map_err(|error| PyRuntimeError::new_err(format!("Execution failed: {}", error)))
+        tensor
+            .to_dtype(T::DTYPE)
+            .map_err(|error| PyRuntimeError::new_err(format!("Execution failed: {}", error)))
+    })
 }
 
 pub fn tensor_to_pytensor<T: Element + WithDType>(python: Python, tensor: Tensor) -> PyResult<Bound<PyAny>> {
-    let shape = tensor.dims();
-
-    let data: Vec<T> = tensor
-        .flatten_all()
-        .map_err(|error| PyErr::new::<PyRuntimeError, _>(error.to_string()))?
-        .to_vec1::<_>()
-        .map_err(|error| PyErr::new::<PyRuntimeError, _>(error.to_string()))?;
-
-    let array = PyArray::from_iter(python, data)
-        .reshape(shape)
-        .map_err(|error| PyErr::new::<PyRuntimeError, _>(error.to_string()))?;
+    torch::into_torch(python, &tensor, TorchDType::from(T::DTYPE))
+}
 
-    let torch = python.import("torch")?;
-    let tensor = torch.getattr("tensor")?.call1((array,))?;
+/// Like [`tensor_to_pytensor`], but placed according to the current [`OutputPlacement`].
+pub fn tensor_to_placed_pytensor<'py, T: Element + WithDType>(
+    python: Python<'py>,
+    tensor: Tensor,
+    source: Option<&Placement>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let placement = OutputPlacement::current().resolve(TorchDType::from(T::DTYPE), source);
 
-    Ok(tensor)
+    torch::into_torch_at(python, &tensor, &placement)
 }
@@ -0,0 +1,153 @@
+//! Conversions between [`Image`] and the `image` crate, enabled with the `image` feature.
+//!
+//! Pixels are scaled to `0.0..=1.0` on the way in, whatever the bit depth of the source,
+//! and images are handed back as 32-bit float images so no precision is lost on the way out.
+//! Use [`DynamicImage::to_rgb8`] or [`DynamicImage::to_rgba16`] to pick a bit depth.
+
+use crate::types::image::Image;
+use crate::types::mask::Mask;
+use candle_core::{D, DType, Device, Tensor, WithDType};
+use image::{DynamicImage, ImageBuffer, Rgb32FImage, Rgba32FImage};
+use numpy::Element;
+
+fn frame_from_dynamic(image: &DynamicImage) -> candle_core::Result<Tensor> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+
+    match image.color().has_alpha() {
+        true => Tensor::from_vec(image.to_rgba32f().into_raw(), (1, height, width, 4), &Device::Cpu),
+        false => Tensor::from_vec(image.to_rgb32f().into_raw(), (1, height, width, 3), &Device::Cpu),
+    }
+}
+
+fn batch_from_dynamic<T: Element + WithDType>(images: &[DynamicImage]) -> candle_core::Result<Tensor> {
+    if images.is_empty() {
+        candle_core::bail!("can not create an image from an empty batch")
+    }
+
+    let frames = images
+        .iter()
+        .map(frame_from_dynamic)
+        .collect::<candle_core::Result<Vec<_>>>()?;
+
+    Tensor::cat(&frames, 0)?.to_dtype(T::DTYPE)
+}
+
+fn frame_to_dynamic(frame: &Tensor) -> candle_core::Result<DynamicImage> {
+    let (height, width, channels) = frame.dims3()?;
+
+    // Grayscale frames are expanded to RGB, as `DynamicImage` has no single channel float variant.
+    let frame = match channels {
+        1 => frame.repeat((1, 1, 3))?,
+        2 => Tensor::cat(&[frame.narrow(2, 0, 1)?.repeat((1, 1, 3))?, frame.narrow(2, 1, 1)?], 2)?,
+        _ => frame.clone(),
+    };
+
+    let data = frame.to_dtype(DType::F32)?.flatten_all()?.to_vec1::<f32>()?;
+    let (width, height) = (width as u32, height as u32);
+
+    let image = match frame.dim(D::Minus1)? {
+        3 => Rgb32FImage::from_raw(width, height, data).map(DynamicImage::ImageRgb32F),
+        _ => Rgba32FImage::from_raw(width, height, data).map(DynamicImage::ImageRgba32F),
+    };
+
+    image.ok_or_else(|| candle_core::Error::Msg("frame does not match its dimensions".to_string()))
+}
+
+impl<T: Element + WithDType> TryFrom<&DynamicImage> for Image<T> {
+    type Error = candle_core::Error;
+
+    /// A batch of one, with an alpha channel only if the source has one.
+    fn try_from(image: &DynamicImage) -> Result<Self, Self::Error> {
+        Image::try_from_tensor(batch_from_dynamic::<T>(std::slice::from_ref(image))?)
+    }
+}
+
+impl<T: Element + WithDType> TryFrom<DynamicImage> for Image<T> {
+    type Error = candle_core::Error;
+
+    fn try_from(image: DynamicImage) -> Result<Self, Self::Error> {
+        Image::try_from(&image)
+    }
+}
+
+impl<T: Element + WithDType> TryFrom<&[DynamicImage]> for Image<T> {
+    type Error = candle_core::Error;
+
+    /// A batch of all `images`, which must share the same size and either all have alpha or none.
+    fn try_from(images: &[DynamicImage]) -> Result<Self, Self::Error> {
+        Image::try_from_tensor(batch_from_dynamic::<T>(images)?)
+    }
+}
+
+impl<T: Element + WithDType> TryFrom<&Image<T>> for DynamicImage {
+    type Error = candle_core::Error;
+
+    /// The only image of a batch of one.
+    fn try_from(image: &Image<T>) -> Result<Self, Self::Error> {
+        let batch_size = image.batch_size()?;
+
+        if batch_size != 1 {
+            candle_core::bail!("expected a batch of one image, received {}", batch_size)
+        }
+
+        frame_to_dynamic(&image.get(0)?)
+    }
+}
+
+impl<T: Element + WithDType> TryFrom<&Image<T>> for Vec<DynamicImage> {
+    type Error = candle_core::Error;
+
+    fn try_from(image: &Image<T>) -> Result<Self, Self::Error> {
+        (0..image.batch_size()?)
+            .map(|index| frame_to_dynamic(&image.get(index)?))
+            .collect()
+    }
+}
+
+impl<T: Element + WithDType> Image<T> {
+    /// Split images into their RGB channels and a mask, the same way `LoadImage` does.
+    ///
+    /// The mask is the inverted alpha channel, so transparent pixels are `1.0`, and is
+    /// all zeros for images without transparency.
+    pub fn from_dynamic_with_mask(images: &[DynamicImage]) -> candle_core::Result<(Image<T>, Mask<T>)> {
+        let frames = images
+            .iter()
+            .map(|image| {
+                let (width, height) = (image.width() as usize, image.height() as usize);
+                let rgba = image.to_rgba32f().into_raw();
+
+                Tensor::from_vec(rgba, (1, height, width, 4), &Device::Cpu)
+            })
+            .collect::<candle_core::Result<Vec<_>>>()?;
+
+        if frames.is_empty() {
+            candle_core::bail!("can not create an image from an empty batch")
+        }
+
+        let batch = Tensor::cat(&frames, 0)?.to_dtype(T::DTYPE)?;
+        let alpha = batch.narrow(3, 3, 1)?.squeeze(3)?;
+
+        Ok((
+            Image::try_from_tensor(batch.narrow(3, 0, 3)?.contiguous()?)?,
+            Mask::try_from_tensor(alpha.affine(-1.0, 1.0)?.contiguous()?)?,
+        ))
+    }
+}
+
+impl<T: Element + WithDType> TryFrom<&Mask<T>> for Vec<ImageBuffer<image::Luma<f32>, Vec<f32>>> {
+    type Error = candle_core::Error;
+
+    /// Each mask of the batch as a grayscale image.
+    fn try_from(mask: &Mask<T>) -> Result<Self, Self::Error> {
+        let (batch_size, height, width) = mask.dims3()?;
+
+        (0..batch_size)
+            .map(|index| {
+                let data = mask.get(index)?.to_dtype(DType::F32)?.flatten_all()?.to_vec1::<f32>()?;
+
+                ImageBuffer::from_raw(width as u32, height as u32, data)
+                    .ok_or_else(|| candle_core::Error::Msg("mask does not match its dimensions".to_string()))
+            })
+            .collect()
+    }
+}
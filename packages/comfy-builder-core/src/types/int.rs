@@ -5,28 +5,45 @@ use pyo3::prelude::PyAnyMethods;
 use pyo3::types::PyDict;
 use pyo3::{Bound, IntoPyObject, PyAny, PyResult};
 
+/// Step used by float widgets unless the field sets its own `#[step = ...]`.
+pub const DEFAULT_FLOAT_STEP: f64 = 0.01;
+
+/// Primitive types that can back a numeric widget, deciding whether it renders as `Int` or `Float`.
+pub trait Numeric: Num + Bounded + PartialOrd {
+    const COMFY_TYPE: ComfyType;
+}
+
 macro_rules! impl_comfy_type {
     ($($primitive:ty => $ctype:expr),*) => {
         $(
+            impl Numeric for $primitive {
+                const COMFY_TYPE: ComfyType = $ctype;
+            }
+
             impl<'py> AsInput<'py> for $primitive {
                 fn comfy_type() -> ComfyType {
                     $ctype
                 }
 
                 fn set_options(dict: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
-                    if $ctype == ComfyType::Float {
-                        if dict.get_item("step").is_err() {
-                            dict.set_item("step", 0.01)?;
-                        }
-                    }
-
-                    numeric_defaults::<$primitive>(dict)
+                    numeric_options::<$primitive>(dict)
                 }
             }
         )*
     };
 }
 
+pub fn numeric_options<'py, T>(dict: &Bound<'py, PyDict>) -> PyResult<()>
+where
+    T: Numeric + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
+{
+    if T::COMFY_TYPE == ComfyType::Float && dict.get_item("step").is_err() {
+        dict.set_item("step", DEFAULT_FLOAT_STEP)?;
+    }
+
+    numeric_defaults::<T>(dict)
+}
+
 pub fn numeric_defaults<'py, T>(dict: &Bound<'py, PyDict>) -> PyResult<()>
 where
     T: Num + Bounded + PartialOrd + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
@@ -1,15 +1,27 @@
 use crate::types::comfy_type::{AsInput, ComfyType};
-use crate::types::image::Image;
+use crate::types::image::{Image, torch_to_candle};
+use crate::types::torch::Placement;
 use candle_core::{Device, WithDType};
 use numpy::Element;
-use pyo3::prelude::PyAnyMethods;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods};
 use pyo3::types::PyDict;
-use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
 
-#[derive(Clone, Debug)]
+/// ComfyUI `LATENT`, a dict holding the `samples` tensor and optional metadata.
+///
+/// Samples are usually `[B, C, H, W]`, or `[B, C, T, H, W]` for video models, so unlike
+/// images they are kept as decoded, without checking their layout.
+///
+/// Keys that are not mapped to a typed field are kept in `extra` and written back untouched,
+/// so that values set by other nodes, e.g. `downscale_ratio_spacial`, survive a round-trip.
+#[derive(Debug)]
 pub struct Latent<T: Element + WithDType = f32> {
     samples: Image<T>,
     noise_mask: Option<Image<T>>,
+    batch_index: Option<Vec<usize>>,
+    latent_type: Option<String>,
+    extra: Vec<(String, Py<PyAny>)>,
 }
 
 impl<'py, T: Element + WithDType> FromPyObject<'py> for Latent<T> {
@@ -29,15 +41,119 @@ impl<T: Element + WithDType> Latent<T> {
         let dict = any.downcast::<PyDict>()?;
 
         let samples = dict
-            .get_item("samples")
-            .map(|samples| Image::<T>::new(samples, &Device::Cpu))??;
+            .get_item("samples")?
+            .ok_or_else(|| PyValueError::new_err("invalid latent: missing `samples`"))?;
 
-        let noise_mask = dict
-            .get_item("noise_mask")
-            .and_then(|noise| Image::<T>::new(noise, &Device::Cpu))
-            .ok();
+        let mut latent = Self::from(decode::<T>(samples)?);
 
-        Ok(Self { samples, noise_mask })
+        for (key, value) in dict.iter() {
+            let key = key.extract::<String>()?;
+
+            if key == "samples" {
+                continue;
+            }
+
+            if value.is_none() {
+                latent.extra.push((key, value.unbind()));
+                continue;
+            }
+
+            let invalid = |error: PyErr| PyValueError::new_err(format!("invalid latent `{}`: {}", key, error));
+
+            match key.as_str() {
+                "noise_mask" => latent.noise_mask = Some(decode::<T>(value).map_err(invalid)?),
+                "batch_index" => latent.batch_index = Some(value.extract().map_err(invalid)?),
+                "type" => latent.latent_type = Some(value.extract().map_err(invalid)?),
+                _ => latent.extra.push((key, value.unbind())),
+            }
+        }
+
+        Ok(latent)
+    }
+
+    pub fn samples(&self) -> &Image<T> {
+        &self.samples
+    }
+
+    pub fn set_samples(&mut self, samples: Image<T>) {
+        self.samples = samples;
+    }
+
+    pub fn into_samples(self) -> Image<T> {
+        self.samples
+    }
+
+    /// The device and dtype of the samples this latent was decoded from, see [`Image::placement`].
+    pub fn placement(&self) -> Option<&Placement> {
+        self.samples.placement()
+    }
+
+    pub fn noise_mask(&self) -> Option<&Image<T>> {
+        self.noise_mask.as_ref()
+    }
+
+    pub fn set_noise_mask(&mut self, noise_mask: Option<Image<T>>) {
+        self.noise_mask = noise_mask;
+    }
+
+    /// The position of each sample within the original batch, used to pick matching noise.
+    pub fn batch_index(&self) -> Option<&[usize]> {
+        self.batch_index.as_deref()
+    }
+
+    pub fn set_batch_index(&mut self, batch_index: Option<Vec<usize>>) {
+        self.batch_index = batch_index;
+    }
+
+    /// The `type` key, set by models whose latents are not images, e.g. `audio`.
+    pub fn latent_type(&self) -> Option<&str> {
+        self.latent_type.as_deref()
+    }
+
+    pub fn set_latent_type(&mut self, latent_type: Option<String>) {
+        self.latent_type = latent_type;
+    }
+
+    pub fn extra(&self) -> &[(String, Py<PyAny>)] {
+        &self.extra
+    }
+
+    /// Look up a key without a typed accessor, such as `downscale_ratio_spacial`.
+    pub fn get_extra<'py>(&self, python: Python<'py>, key: &str) -> Option<&Bound<'py, PyAny>> {
+        self.extra
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.bind(python))
+    }
+
+    /// Set a key without a typed accessor, replacing any previous value.
+    pub fn set_extra(&mut self, key: impl Into<String>, value: Py<PyAny>) {
+        let key = key.into();
+
+        match self.extra.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, previous)) => *previous = value,
+            None => self.extra.push((key, value)),
+        }
+    }
+
+    pub fn clone_ref(&self, python: Python) -> Self {
+        Self {
+            samples: self.samples.clone(),
+            noise_mask: self.noise_mask.clone(),
+            batch_index: self.batch_index.clone(),
+            latent_type: self.latent_type.clone(),
+            extra: self
+                .extra
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone_ref(python)))
+                .collect(),
+        }
+    }
+}
+
+impl<T: Element + WithDType> Clone for Latent<T> {
+    fn clone(&self) -> Self {
+        Python::attach(|python| self.clone_ref(python))
     }
 }
 
@@ -55,6 +171,18 @@ impl<'py, T: Element + WithDType> IntoPyObject<'py> for Latent<T> {
             dic.set_item("noise_mask", noise)?;
         }
 
+        if let Some(batch_index) = self.batch_index {
+            dic.set_item("batch_index", batch_index)?;
+        }
+
+        if let Some(latent_type) = self.latent_type {
+            dic.set_item("type", latent_type)?;
+        }
+
+        for (key, value) in self.extra {
+            dic.set_item(key, value)?;
+        }
+
         Ok(dic.into_any())
     }
 }
@@ -64,6 +192,16 @@ impl<T: Element + WithDType> From<Image<T>> for Latent<T> {
         Latent {
             samples: tensor,
             noise_mask: None,
+            batch_index: None,
+            latent_type: None,
+            extra: vec![],
         }
     }
 }
+
+/// Latent tensors are not laid out like images, so they are wrapped without checking their shape.
+fn decode<T: Element + WithDType>(tensor: Bound<PyAny>) -> PyResult<Image<T>> {
+    let placement = Placement::of(&tensor)?;
+
+    Ok(Image::from_tensor(torch_to_candle::<T>(tensor, &Device::Cpu)?).with_placement(Some(placement)))
+}
@@ -1,18 +1,29 @@
 use crate::types::comfy_type::{AsInput, ComfyType};
-use crate::types::image::Image;
+use crate::types::image::{Image, tensor_to_placed_pytensor, torch_to_candle};
+use crate::types::torch::Placement;
 use candle_core::shape::ShapeWithOneHole;
 use candle_core::{Device, Tensor as CandleTensor, WithDType};
 use numpy::Element;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::PyAnyMethods;
 use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::marker::PhantomData;
 use std::ops::Deref;
 
+/// ComfyUI `MASK`, a batch of single channel masks laid out as `[B, H, W]`.
+///
+/// Like [`Image`], the layout is checked when decoded from ComfyUI or built with
+/// [`Mask::try_from_tensor`] and [`Mask::try_from_image`].
 #[derive(Clone, Debug)]
-pub struct Mask<T: Element + WithDType>(Image<T>);
+pub struct Mask<T: Element + WithDType> {
+    tensor: CandleTensor,
+    placement: Option<Placement>,
+    marker: PhantomData<T>,
+}
 
 impl<'py, T: Element + WithDType> FromPyObject<'py> for Mask<T> {
     fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
-        Ok(Image::new(object.extract::<Bound<'py, PyAny>>()?, &Device::Cpu)?.into())
+        Mask::new(object.extract::<Bound<'py, PyAny>>()?, &Device::Cpu)
     }
 }
 
@@ -22,11 +33,87 @@ impl<'py, T: Element + WithDType> AsInput<'py> for Mask<T> {
     }
 }
 
+impl<T: Element + WithDType> Mask<T> {
+    pub fn new(any: Bound<PyAny>, device: &Device) -> PyResult<Self> {
+        let placement = Placement::of(&any)?;
+
+        Self::try_from_tensor(torch_to_candle::<T>(any, device)?)
+            .map(|mask| mask.with_placement(Some(placement)))
+            .map_err(|error| PyValueError::new_err(format!("invalid mask: {}", error)))
+    }
+
+    /// Wrap a tensor as is, without checking its layout.
+    pub fn from_tensor(tensor: CandleTensor) -> Self {
+        Self {
+            tensor,
+            placement: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Wrap a `[B, H, W]` tensor, failing for any other shape.
+    pub fn try_from_tensor(tensor: CandleTensor) -> candle_core::Result<Self> {
+        tensor.dims3()?;
+
+        Ok(Self::from_tensor(tensor))
+    }
+
+    /// Take the only channel of a single channel `[B, H, W, 1]` image as the mask.
+    pub fn try_from_image(image: Image<T>) -> candle_core::Result<Self> {
+        let channels = image.channels()?;
+
+        if channels != 1 {
+            candle_core::bail!("expected a single channel image, received {} channels", channels)
+        }
+
+        let placement = image.placement().cloned();
+
+        Ok(Mask::try_from_tensor(image.into_tensor().squeeze(3)?)?.with_placement(placement))
+    }
+
+    /// The device and dtype of the tensor this mask was decoded from, see [`Image::placement`].
+    pub fn placement(&self) -> Option<&Placement> {
+        self.placement.as_ref()
+    }
+
+    pub fn with_placement(self, placement: Option<Placement>) -> Self {
+        Self { placement, ..self }
+    }
+
+    pub fn from_raw<U: ShapeWithOneHole>(data: Vec<T>, shape: U, device: &Device) -> candle_core::Result<Self> {
+        Ok(Self::from_tensor(CandleTensor::from_vec(data, shape, device)?))
+    }
+
+    pub fn into_tensor(self) -> CandleTensor {
+        self.tensor
+    }
+
+    /// The size of the batch, failing unless the mask is laid out as `[B, H, W]`.
+    pub fn batch_size(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims3()?.0)
+    }
+
+    pub fn height(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims3()?.1)
+    }
+
+    pub fn width(&self) -> candle_core::Result<usize> {
+        Ok(self.tensor.dims3()?.2)
+    }
+
+    /// Each mask of the batch as a batch of one.
+    pub fn frames(&self) -> candle_core::Result<impl Iterator<Item = candle_core::Result<Mask<T>>> + '_> {
+        Ok((0..self.batch_size()?).map(|index| {
+            Ok(Self::from_tensor(self.tensor.narrow(0, index, 1)?).with_placement(self.placement.clone()))
+        }))
+    }
+}
+
 impl<T: Element + WithDType> Deref for Mask<T> {
     type Target = CandleTensor;
 
     fn deref(&self) -> &Self::Target {
-        self.0.inner_tensor()
+        &self.tensor
     }
 }
 
@@ -36,7 +123,7 @@ impl<'py, T: Element + WithDType> IntoPyObject<'py> for Mask<T> {
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        self.0.into_pyobject(py)
+        tensor_to_placed_pytensor::<T>(py, self.tensor, self.placement.as_ref())
     }
 }
 
@@ -44,12 +131,15 @@ impl<T: Element + WithDType, S: ShapeWithOneHole> TryFrom<(Vec<T>, S, &Device)>
     type Error = candle_core::Error;
 
     fn try_from(value: (Vec<T>, S, &Device)) -> Result<Self, Self::Error> {
-        Ok(Mask(Image::from_raw(value.0, value.1, value.2)?))
+        Mask::from_raw(value.0, value.1, value.2)
     }
 }
 
+/// Wrap the tensor of an image as is, see [`Mask::try_from_image`] for a checked conversion.
 impl<T: Element + WithDType> From<Image<T>> for Mask<T> {
-    fn from(tensor: Image<T>) -> Self {
-        Mask(tensor)
+    fn from(image: Image<T>) -> Self {
+        let placement = image.placement().cloned();
+
+        Mask::from_tensor(image.into_tensor()).with_placement(placement)
     }
 }
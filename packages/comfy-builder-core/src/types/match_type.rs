@@ -0,0 +1,69 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+
+/// Template socket whose type is resolved from whatever gets connected to it.
+///
+/// Every `Match` field sharing the same `ID`, inputs and outputs alike, is bound to the same
+/// `io.MatchType.Template`, so once one of them is linked the others only accept that type.
+#[derive(Debug)]
+pub struct Match<const ID: char = 'T'> {
+    object: Py<PyAny>,
+}
+
+impl<const ID: char> Match<ID> {
+    pub fn new(object: Py<PyAny>) -> Self {
+        Self { object }
+    }
+
+    pub fn bind<'py>(&self, python: Python<'py>) -> &Bound<'py, PyAny> {
+        self.object.bind(python)
+    }
+
+    pub fn clone_ref(&self, python: Python) -> Self {
+        Self::new(self.object.clone_ref(python))
+    }
+
+    pub fn into_inner(self) -> Py<PyAny> {
+        self.object
+    }
+
+    pub fn extract<'py, T: FromPyObject<'py>>(&self, python: Python<'py>) -> PyResult<T> {
+        self.object.bind(python).extract::<T>()
+    }
+
+    fn template<'py>(io: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        io.getattr("MatchType")?.getattr("Template")?.call1((ID.to_string(),))
+    }
+}
+
+impl<'py, const ID: char> FromPyObject<'py> for Match<ID> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::new(object.clone().unbind()))
+    }
+}
+
+impl<'py, const ID: char> AsInput<'py> for Match<ID> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Match
+    }
+
+    fn set_options(dict: &mut Bound<'py, PyDict>, io: &Bound<'py, PyAny>) -> PyResult<()> {
+        dict.set_item("template", Self::template(io)?)
+    }
+
+    fn set_output_options(dict: &mut Bound<'py, PyDict>, io: &Bound<'py, PyAny>) -> PyResult<()> {
+        dict.set_item("template", Self::template(io)?)
+    }
+}
+
+impl<'py, const ID: char> IntoPyObject<'py> for Match<ID> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.object.into_bound(python))
+    }
+}
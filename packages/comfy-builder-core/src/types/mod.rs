@@ -1,10 +1,31 @@
+pub mod any;
+pub mod audio;
+pub mod autogrow;
 pub mod boolean;
+pub mod bounding_box;
+pub mod color;
 pub mod comfy_type;
+pub mod conditioning;
+pub mod dlpack;
+pub mod dynamic_options;
+pub mod guider;
+pub mod handle;
 pub mod image;
+#[cfg(feature = "image")]
+pub mod image_conversions;
 pub mod int;
 pub mod latent;
 pub mod mask;
+pub mod match_type;
+pub mod multi_select;
+pub mod point;
+pub mod ranged;
+pub mod sampler;
 pub mod seed;
+pub mod shared;
 pub mod sigmas;
 pub mod slider;
 pub mod string;
+pub mod tensor_cache;
+pub mod torch;
+pub mod video;
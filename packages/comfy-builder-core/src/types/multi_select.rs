@@ -0,0 +1,72 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::{PyDict, PyList};
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::ops::Deref;
+
+/// Multi-select combo built from the variants of a `#[derive(Enum)]`.
+///
+/// Unlike a plain `Vec<E>` field, this does not switch the node into list mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSelect<E> {
+    values: Vec<E>,
+}
+
+impl<E> MultiSelect<E> {
+    pub fn new(values: Vec<E>) -> Self {
+        Self { values }
+    }
+
+    pub fn into_inner(self) -> Vec<E> {
+        self.values
+    }
+}
+
+impl<E> Deref for MultiSelect<E> {
+    type Target = Vec<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<E> From<Vec<E>> for MultiSelect<E> {
+    fn from(values: Vec<E>) -> Self {
+        Self::new(values)
+    }
+}
+
+impl<E> IntoIterator for MultiSelect<E> {
+    type Item = E;
+    type IntoIter = std::vec::IntoIter<E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'py, E: FromPyObject<'py>> FromPyObject<'py> for MultiSelect<E> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::new(object.extract::<Vec<E>>()?))
+    }
+}
+
+impl<'py, E: AsInput<'py>> AsInput<'py> for MultiSelect<E> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::MultiCombo
+    }
+
+    fn set_options(dict: &mut Bound<'py, PyDict>, io: &Bound<'py, PyAny>) -> PyResult<()> {
+        E::set_options(dict, io)
+    }
+}
+
+impl<'py, E: IntoPyObject<'py>> IntoPyObject<'py> for MultiSelect<E> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyList::new(python, self.values)?.into_any())
+    }
+}
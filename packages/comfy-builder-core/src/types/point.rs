@@ -0,0 +1,54 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+
+/// Pixel coordinate exchanged as a `{"x": ..., "y": ...}` dict.
+///
+/// `POINT` is a link-only socket: ComfyUI has no widget for it, so it has to be connected to the
+/// output of another node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<'py> FromPyObject<'py> for Point {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok((x, y)) = object.extract::<(usize, usize)>() {
+            return Ok(Self::new(x, y));
+        }
+
+        Ok(Self::new(
+            object.get_item("x")?.extract()?,
+            object.get_item("y")?.extract()?,
+        ))
+    }
+}
+
+impl<'py> AsInput<'py> for Point {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Custom("POINT")
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Point {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dict = PyDict::new(python);
+
+        dict.set_item("x", self.x)?;
+        dict.set_item("y", self.y)?;
+
+        Ok(dict.into_any())
+    }
+}
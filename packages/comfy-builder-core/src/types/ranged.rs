@@ -0,0 +1,160 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::int::{Numeric, numeric_options};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::fmt::Display;
+use std::ops::Deref;
+
+/// The bounds are emitted into the schema and checked again once the value is decoded,
+/// so values submitted straight through the API can not bypass the widget limits.
+///
+/// NaN is never within the range, as it does not compare to either bound.
+fn check_range<T: PartialOrd + Display>(value: T, min: T, max: T) -> PyResult<T> {
+    if !(&min..=&max).contains(&&value) {
+        return Err(PyValueError::new_err(format!(
+            "{} is outside the allowed range [{}, {}]",
+            value, min, max
+        )));
+    }
+
+    Ok(value)
+}
+
+fn set_bounds<'py, T>(dict: &Bound<'py, PyDict>, min: T, max: T) -> PyResult<()>
+where
+    T: Numeric + IntoPyObject<'py> + for<'a> pyo3::conversion::FromPyObjectBound<'a, 'py>,
+{
+    dict.set_item("min", min)?;
+    dict.set_item("max", max)?;
+
+    numeric_options::<T>(dict)
+}
+
+macro_rules! impl_ranged {
+    ($name:ident, $primitive:ty, [$($definition:tt)*], [$($bounds:tt)*], [$($arguments:tt)*], $min:expr, $max:expr) => {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name<$($definition)*> {
+            value: $primitive,
+        }
+
+        impl<$($bounds)*> $name<$($arguments)*> {
+            const VALID: () = assert!($min <= $max, "the minimum bound must not be greater than the maximum bound");
+
+            pub fn new(value: $primitive) -> PyResult<Self> {
+                let () = Self::VALID;
+
+                Ok(Self {
+                    value: check_range(value, Self::min(), Self::max())?,
+                })
+            }
+
+            pub fn min() -> $primitive {
+                $min
+            }
+
+            pub fn max() -> $primitive {
+                $max
+            }
+
+            pub fn into_inner(self) -> $primitive {
+                self.value
+            }
+        }
+
+        impl<$($bounds)*> Deref for $name<$($arguments)*> {
+            type Target = $primitive;
+
+            fn deref(&self) -> &Self::Target {
+                &self.value
+            }
+        }
+
+        impl<$($bounds)*> TryFrom<$primitive> for $name<$($arguments)*> {
+            type Error = PyErr;
+
+            fn try_from(value: $primitive) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl<'py, $($bounds)*> FromPyObject<'py> for $name<$($arguments)*> {
+            fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+                Self::new(object.extract::<$primitive>()?)
+            }
+        }
+
+        impl<'py, $($bounds)*> AsInput<'py> for $name<$($arguments)*> {
+            fn comfy_type() -> ComfyType {
+                <$primitive as Numeric>::COMFY_TYPE
+            }
+
+            fn set_options(dict: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
+                set_bounds::<$primitive>(dict, Self::min(), Self::max())
+            }
+        }
+
+        impl<'py, $($bounds)*> IntoPyObject<'py> for $name<$($arguments)*> {
+            type Target = <$primitive as IntoPyObject<'py>>::Target;
+            type Output = <$primitive as IntoPyObject<'py>>::Output;
+            type Error = <$primitive as IntoPyObject<'py>>::Error;
+
+            fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+                self.value.into_pyobject(python)
+            }
+        }
+    };
+}
+
+macro_rules! impl_ranged_integer {
+    ($($name:ident => $primitive:ty),*) => {
+        $(
+            impl_ranged!(
+                $name,
+                $primitive,
+                [const MIN: $primitive, const MAX: $primitive],
+                [const MIN: $primitive, const MAX: $primitive],
+                [MIN, MAX],
+                MIN,
+                MAX
+            );
+        )*
+    };
+}
+
+// Floats can not be used as const generics, so their bounds are given as integers
+// divided by `SCALE`, e.g. `RangedF32<-25, 25, 10>` accepts values from -2.5 to 2.5.
+macro_rules! impl_ranged_float {
+    ($($name:ident => $primitive:ty),*) => {
+        $(
+            impl_ranged!(
+                $name,
+                $primitive,
+                [const MIN: i64, const MAX: i64, const SCALE: u32 = 1],
+                [const MIN: i64, const MAX: i64, const SCALE: u32],
+                [MIN, MAX, SCALE],
+                MIN as $primitive / SCALE as $primitive,
+                MAX as $primitive / SCALE as $primitive
+            );
+        )*
+    };
+}
+
+impl_ranged_integer!(
+    RangedU8 => u8,
+    RangedU16 => u16,
+    RangedU32 => u32,
+    RangedU64 => u64,
+    RangedUsize => usize,
+    RangedI8 => i8,
+    RangedI16 => i16,
+    RangedI32 => i32,
+    RangedI64 => i64,
+    RangedIsize => isize
+);
+
+impl_ranged_float!(
+    RangedF32 => f32,
+    RangedF64 => f64
+);
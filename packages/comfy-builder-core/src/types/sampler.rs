@@ -0,0 +1,178 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::{tensor_to_pytensor, torch_to_candle};
+use candle_core::{Device, Tensor};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods};
+use pyo3::types::{PyCFunction, PyDict, PyTuple};
+use pyo3::{Bound, FromPyObject, IntoPyObject, Py, PyAny, PyErr, PyResult, Python};
+use std::error::Error;
+use std::sync::Arc;
+
+pub type SamplingError = Box<dyn Error + Send + Sync>;
+
+/// The model as seen from inside a sampling loop.
+pub trait Denoiser {
+    /// Predict the fully denoised sample from `x` at noise level `sigma`.
+    fn denoise(&self, x: &Tensor, sigma: f64) -> Result<Tensor, SamplingError>;
+
+    /// Report the result of a step, which drives ComfyUI's previews and progress bar.
+    fn report(&self, _step: usize, _x: &Tensor, _sigma: f64, _denoised: &Tensor) -> Result<(), SamplingError> {
+        Ok(())
+    }
+}
+
+/// A sampling loop implemented in Rust, equivalent to the `sample_*` functions of k-diffusion.
+pub trait SamplerFunction: Send + Sync + 'static {
+    /// Denoise `latent`, which already has the initial noise applied, following the `sigmas` schedule.
+    fn sample(&self, model: &dyn Denoiser, latent: Tensor, sigmas: &Tensor) -> Result<Tensor, SamplingError>;
+}
+
+enum SamplerKind {
+    Rust(Arc<dyn SamplerFunction>),
+    Python(Py<PyAny>),
+}
+
+/// ComfyUI `SAMPLER`, either a Rust [`SamplerFunction`] or a sampler received from another node.
+pub struct Sampler {
+    kind: SamplerKind,
+}
+
+impl Sampler {
+    pub fn new<S: SamplerFunction>(sampler: S) -> Self {
+        Self {
+            kind: SamplerKind::Rust(Arc::new(sampler)),
+        }
+    }
+
+    pub fn from_object(object: Py<PyAny>) -> Self {
+        Self {
+            kind: SamplerKind::Python(object),
+        }
+    }
+}
+
+struct PythonDenoiser<'py> {
+    model: Bound<'py, PyAny>,
+    extra_args: Bound<'py, PyDict>,
+    callback: Option<Bound<'py, PyAny>>,
+    like: Bound<'py, PyAny>,
+}
+
+impl<'py> Denoiser for PythonDenoiser<'py> {
+    fn denoise(&self, x: &Tensor, sigma: f64) -> Result<Tensor, SamplingError> {
+        let python = self.model.py();
+        let x = to_torch(python, x.clone(), &self.like)?;
+        let sigmas = x
+            .call_method1("new_ones", ((x.len()?,),))?
+            .call_method1("__mul__", (sigma,))?;
+
+        let denoised = self.model.call((x, sigmas), Some(&self.extra_args))?;
+
+        Ok(to_candle(&denoised)?)
+    }
+
+    fn report(&self, step: usize, x: &Tensor, sigma: f64, denoised: &Tensor) -> Result<(), SamplingError> {
+        if let Some(callback) = &self.callback {
+            let python = callback.py();
+            let state = PyDict::new(python);
+
+            state.set_item("i", step)?;
+            state.set_item("x", to_torch(python, x.clone(), &self.like)?)?;
+            state.set_item("sigma", sigma)?;
+            state.set_item("sigma_hat", sigma)?;
+            state.set_item("denoised", to_torch(python, denoised.clone(), &self.like)?)?;
+
+            callback.call1((state,))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Bring a torch tensor living on any device and dtype into a `f32` candle tensor.
+pub(crate) fn to_candle(tensor: &Bound<PyAny>) -> PyResult<Tensor> {
+    let tensor = tensor
+        .call_method0("detach")?
+        .call_method0("cpu")?
+        .call_method0("float")?;
+
+    torch_to_candle::<f32>(tensor, &Device::Cpu)
+}
+
+/// Turn a candle tensor back into torch, on the same device and dtype as `like`.
+pub(crate) fn to_torch<'py>(
+    python: Python<'py>,
+    tensor: Tensor,
+    like: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tensor = tensor
+        .to_dtype(candle_core::DType::F32)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+
+    tensor_to_pytensor::<f32>(python, tensor)?.call_method1("to", (like.getattr("device")?, like.getattr("dtype")?))
+}
+
+fn optional_kwarg<'py>(kwargs: Option<&Bound<'py, PyDict>>, key: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    Ok(match kwargs {
+        Some(kwargs) => kwargs.get_item(key)?.filter(|value| !value.is_none()),
+        None => None,
+    })
+}
+
+fn run_sampler(
+    function: &dyn SamplerFunction,
+    args: &Bound<PyTuple>,
+    kwargs: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    let python = args.py();
+    let (model, x, sigmas) = args.extract::<(Bound<PyAny>, Bound<PyAny>, Bound<PyAny>)>()?;
+
+    let extra_args = match optional_kwarg(kwargs, "extra_args")? {
+        Some(extra_args) => extra_args.downcast_into::<PyDict>()?,
+        None => PyDict::new(python),
+    };
+
+    let denoiser = PythonDenoiser {
+        model,
+        extra_args,
+        callback: optional_kwarg(kwargs, "callback")?,
+        like: x.clone(),
+    };
+
+    let output = function
+        .sample(&denoiser, to_candle(&x)?, &to_candle(&sigmas)?)
+        .map_err(|error| PyRuntimeError::new_err(format!("Sampling failed: {}", error)))?;
+
+    Ok(to_torch(python, output, &x)?.unbind())
+}
+
+impl<'py> FromPyObject<'py> for Sampler {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::from_object(object.clone().unbind()))
+    }
+}
+
+impl<'py> AsInput<'py> for Sampler {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Sampler
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Sampler {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let function = match self.kind {
+            SamplerKind::Python(object) => return Ok(object.into_bound(python)),
+            SamplerKind::Rust(function) => function,
+        };
+
+        let sample = PyCFunction::new_closure(python, Some(c"rust_sampler"), None, move |args, kwargs| {
+            run_sampler(function.as_ref(), args, kwargs)
+        })?;
+
+        python.import("comfy.samplers")?.getattr("KSAMPLER")?.call1((sample,))
+    }
+}
@@ -1,6 +1,5 @@
 use crate::types::comfy_type::{AsInput, ComfyType};
-use crate::types::int::numeric_defaults;
-use num_traits::{Bounded, Num};
+use crate::types::int::{Numeric, numeric_options};
 use pyo3::conversion::FromPyObjectBound;
 use pyo3::prelude::PyAnyMethods;
 use pyo3::types::PyDict;
@@ -27,16 +26,16 @@ impl<T> Deref for Seed<T> {
 
 impl<'py, T> AsInput<'py> for Seed<T>
 where
-    T: Num + Bounded + PartialOrd + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
+    T: Numeric + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
 {
     fn comfy_type() -> ComfyType {
-        ComfyType::Int
+        T::COMFY_TYPE
     }
 
     fn set_options(dict: &mut Bound<'py, PyDict>, _: &Bound<'py, PyAny>) -> PyResult<()> {
         dict.set_item("control_after_generate", true)?;
 
-        numeric_defaults::<T>(dict)
+        numeric_options::<T>(dict)
     }
 }
 
@@ -0,0 +1,119 @@
+use crate::types::comfy_type::{AsInput, ComfyType};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::{PyCapsule, PyCapsuleMethods};
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::any::{Any, type_name};
+use std::ffi::CString;
+use std::ops::Deref;
+use std::sync::Arc;
+
+const CAPSULE_NAME: &str = concat!("comfy_builder_core.shared@", env!("CARGO_PKG_VERSION"));
+
+struct SharedValue {
+    type_name: &'static str,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+/// Any Rust value passed between Rust nodes as-is, without converting it to Python objects.
+///
+/// The value travels through ComfyUI inside a capsule and is handed back to the downstream
+/// node by reference, so connecting one output to several nodes shares the same allocation.
+/// Each `T` gets its own socket type, named after [`type_name`].
+#[derive(Debug)]
+pub struct Shared<T: Send + Sync + 'static> {
+    value: Arc<T>,
+}
+
+impl<T: Send + Sync + 'static> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Self { value: Arc::new(value) }
+    }
+
+    pub fn into_arc(self) -> Arc<T> {
+        self.value
+    }
+
+    /// Take the value out if no other node is holding onto it.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        Arc::try_unwrap(self.value).map_err(|value| Self { value })
+    }
+}
+
+impl<T: Send + Sync + 'static> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Arc<T>> for Shared<T> {
+    fn from(value: Arc<T>) -> Self {
+        Self { value }
+    }
+}
+
+impl<'py, T: Send + Sync + 'static> FromPyObject<'py> for Shared<T> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let capsule = object.downcast::<PyCapsule>().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "expected a Rust value of type `{}`, received a Python object instead",
+                type_name::<T>()
+            ))
+        })?;
+
+        if capsule.name()?.and_then(|name| name.to_str().ok()) != Some(CAPSULE_NAME) {
+            return Err(PyTypeError::new_err(format!(
+                "expected a Rust value of type `{}`, received a foreign capsule",
+                type_name::<T>()
+            )));
+        }
+
+        // SAFETY: capsules carrying `CAPSULE_NAME` are only ever created by `into_pyobject` below,
+        // which always stores a `SharedValue`.
+        let shared = unsafe { capsule.reference::<SharedValue>() };
+
+        Arc::clone(&shared.value)
+            .downcast::<T>()
+            .map(|value| Self { value })
+            .map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "expected a Rust value of type `{}`, received `{}`",
+                    type_name::<T>(),
+                    shared.type_name
+                ))
+            })
+    }
+}
+
+impl<'py, T: Send + Sync + 'static> AsInput<'py> for Shared<T> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Custom(type_name::<T>())
+    }
+}
+
+impl<'py, T: Send + Sync + 'static> IntoPyObject<'py> for Shared<T> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let value = SharedValue {
+            type_name: type_name::<T>(),
+            value: self.value,
+        };
+
+        let name = CString::new(CAPSULE_NAME)?;
+
+        Ok(PyCapsule::new(python, value, Some(name))?.into_any())
+    }
+}
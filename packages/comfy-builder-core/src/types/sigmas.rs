@@ -1,5 +1,6 @@
 use crate::types::comfy_type::{AsInput, AsOutput, ComfyType};
-use crate::types::image::{tensor_to_pytensor, torch_to_candle};
+use crate::types::image::{tensor_to_placed_pytensor, torch_to_candle};
+use crate::types::torch::Placement;
 use candle_core::{DType, Device, Shape, Tensor, WithDType};
 use numpy::Element;
 use pyo3::types::PyAnyMethods;
@@ -8,6 +9,7 @@ use std::marker::PhantomData;
 
 pub struct Sigmas<T = f32> {
     tensor: Tensor,
+    placement: Option<Placement>,
     inner: PhantomData<T>,
 }
 
@@ -15,6 +17,7 @@ impl<T> Sigmas<T> {
     pub fn zeros<S: Into<Shape>>(shape: S, dtype: DType) -> candle_core::Result<Self> {
         Ok(Self {
             tensor: Tensor::zeros(shape.into(), dtype, &Device::Cpu)?,
+            placement: None,
             inner: PhantomData,
         })
     }
@@ -22,11 +25,114 @@ impl<T> Sigmas<T> {
     pub fn blank() -> candle_core::Result<Self> {
         Self::zeros((0, 0), DType::F32)
     }
+
+    pub fn tensor(&self) -> &Tensor {
+        &self.tensor
+    }
+
+    pub fn into_tensor(self) -> Tensor {
+        self.tensor
+    }
+
+    /// The device and dtype of the tensor these sigmas were decoded from, see [`Image::placement`].
+    ///
+    /// [`Image::placement`]: crate::types::image::Image::placement
+    pub fn placement(&self) -> Option<&Placement> {
+        self.placement.as_ref()
+    }
+
+    pub fn with_placement(self, placement: Option<Placement>) -> Self {
+        Self { placement, ..self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tensor.elem_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: WithDType> Sigmas<T> {
+    pub fn from_values(values: &[f64]) -> candle_core::Result<Self> {
+        let values: Vec<T> = values.iter().map(|value| T::from_f64(*value)).collect();
+
+        Ok(Self {
+            tensor: Tensor::new(values, &Device::Cpu)?,
+            placement: None,
+            inner: PhantomData,
+        })
+    }
+
+    pub fn to_vec(&self) -> candle_core::Result<Vec<f64>> {
+        Ok(self
+            .tensor
+            .flatten_all()?
+            .to_vec1::<T>()?
+            .into_iter()
+            .map(|value| value.to_f64())
+            .collect())
+    }
+
+    /// Split the schedule at `step`, both halves share the sigma at the boundary, like `SplitSigmas`.
+    pub fn split(&self, step: usize) -> candle_core::Result<(Self, Self)> {
+        let values = self.to_vec()?;
+        let step = step.min(values.len().saturating_sub(1));
+
+        let (high, low) = match values.is_empty() {
+            true => (Self::from_values(&[])?, Self::from_values(&[])?),
+            false => (
+                Self::from_values(&values[..=step])?,
+                Self::from_values(&values[step..])?,
+            ),
+        };
+
+        Ok((
+            high.with_placement(self.placement.clone()),
+            low.with_placement(self.placement.clone()),
+        ))
+    }
+
+    /// Reverse the schedule, like `FlipSigmas`, so it goes from low to high noise.
+    ///
+    /// A leading zero is nudged to `0.0001` since samplers divide by the first sigma.
+    pub fn flip(&self) -> candle_core::Result<Self> {
+        let mut values = self.to_vec()?;
+
+        values.reverse();
+
+        if let Some(first) = values.first_mut()
+            && *first == 0.0
+        {
+            *first = 0.0001;
+        }
+
+        Ok(Self::from_values(&values)?.with_placement(self.placement.clone()))
+    }
+
+    /// Append `other` to this schedule, the inverse of [`Sigmas::split`].
+    ///
+    /// When `other` starts at the sigma this schedule ends with, the shared sigma is only kept once.
+    pub fn concat(&self, other: &Self) -> candle_core::Result<Self> {
+        let mut values = self.to_vec()?;
+        let other = other.to_vec()?;
+
+        let skip = match (values.last(), other.first()) {
+            (Some(last), Some(first)) => usize::from(last == first),
+            _ => 0,
+        };
+
+        values.extend_from_slice(&other[skip..]);
+
+        Ok(Self::from_values(&values)?.with_placement(self.placement.clone()))
+    }
 }
 
 impl<'py, T: Element + WithDType> FromPyObject<'py> for Sigmas<T> {
     fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
         Ok(Sigmas {
+            placement: Some(Placement::of(object)?),
             tensor: torch_to_candle::<T>(object.extract::<Bound<'py, PyAny>>()?, &Device::Cpu)?,
             inner: PhantomData,
         })
@@ -45,7 +151,7 @@ impl<'py, T: Element + WithDType> IntoPyObject<'py> for Sigmas<T> {
     type Error = PyErr;
 
     fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
-        tensor_to_pytensor::<T>(python, self.tensor)
+        tensor_to_placed_pytensor::<T>(python, self.tensor, self.placement.as_ref())
     }
 }
 
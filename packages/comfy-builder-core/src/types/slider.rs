@@ -1,6 +1,5 @@
 use crate::types::comfy_type::{AsInput, ComfyType};
-use crate::types::int::numeric_defaults;
-use num_traits::{Bounded, Num};
+use crate::types::int::{Numeric, numeric_options};
 use pyo3::conversion::FromPyObjectBound;
 use pyo3::prelude::PyAnyMethods;
 use pyo3::types::PyDict;
@@ -27,16 +26,16 @@ impl<T> Deref for Slider<T> {
 
 impl<'py, T> AsInput<'py> for Slider<T>
 where
-    T: Num + Bounded + PartialOrd + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
+    T: Numeric + IntoPyObject<'py> + for<'a> FromPyObjectBound<'a, 'py>,
 {
     fn comfy_type() -> ComfyType {
-        ComfyType::Int
+        T::COMFY_TYPE
     }
 
     fn set_options(dict: &mut Bound<'py, PyDict>, io: &Bound<'py, PyAny>) -> PyResult<()> {
         dict.set_item("display_mode", io.getattr("NumberDisplay")?.getattr("slider")?)?;
 
-        numeric_defaults::<T>(dict)
+        numeric_options::<T>(dict)
     }
 }
 
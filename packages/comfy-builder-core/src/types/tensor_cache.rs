@@ -0,0 +1,290 @@
+//! Reuse of converted tensors when the same torch tensor is fed into several Rust nodes.
+//!
+//! The cache is disabled until [`configure`] gives it a memory budget. Entries are keyed by the
+//! data pointer, version counter, shape and strides of the torch tensor, so in-place changes on
+//! the torch side make them miss. Each entry only holds a weak reference to its torch tensor and
+//! misses once that tensor is gone, as its memory may then be reused by another one, so the cache
+//! never keeps torch memory alive. The least recently used entries are evicted once the converted
+//! tensors no longer fit into the budget.
+//!
+//! Entries only live for a single prompt execution, see [`enter_execution`], and every lookup
+//! returns a copy, so nodes can never change what is cached.
+
+use crate::types::torch::TorchDType;
+use candle_core::{DType, Device, DeviceLocation, Tensor};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::{PyWeakrefMethods, PyWeakrefReference};
+use pyo3::{Bound, Py, PyAny, PyErr, PyResult, Python};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+static CACHE: LazyLock<Mutex<TensorCache>> = LazyLock::new(|| Mutex::new(TensorCache::default()));
+
+/// Counters to profile how well the cache works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// The number of tensors currently cached.
+    pub entries: usize,
+    /// The memory taken by the tensors currently cached, in bytes.
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    data_ptr: usize,
+    version: u64,
+    shape: Vec<usize>,
+    strides: Vec<isize>,
+    source_device: String,
+    source_dtype: TorchDType,
+    target_dtype: DType,
+    target_device: DeviceLocation,
+}
+
+impl Key {
+    fn of(tensor: &Bound<PyAny>, target_dtype: DType, target_device: &Device) -> PyResult<Self> {
+        Ok(Self {
+            data_ptr: tensor.call_method0("data_ptr")?.extract()?,
+            version: tensor.getattr("_version")?.extract()?,
+            shape: tensor.getattr("shape")?.extract()?,
+            strides: tensor.call_method0("stride")?.extract()?,
+            source_device: tensor.getattr("device")?.str()?.to_string(),
+            source_dtype: TorchDType::from_torch(&tensor.getattr("dtype")?)?,
+            target_dtype,
+            target_device: target_device.location(),
+        })
+    }
+}
+
+struct Entry {
+    tensor: Tensor,
+    bytes: usize,
+    last_used: u64,
+    source: Py<PyWeakrefReference>,
+}
+
+#[derive(Default)]
+struct TensorCache {
+    budget: usize,
+    tick: u64,
+    execution: Option<String>,
+    entries: HashMap<Key, Entry>,
+    stats: CacheStats,
+}
+
+impl TensorCache {
+    fn lock() -> MutexGuard<'static, TensorCache> {
+        CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.budget > 0
+    }
+
+    fn take_entries(&mut self) -> HashMap<Key, Entry> {
+        self.stats.bytes = 0;
+        self.stats.entries = 0;
+
+        std::mem::take(&mut self.entries)
+    }
+
+    /// Only entries whose torch tensor is still `tensor` are hits, anything else is stale and gets
+    /// replaced once converted again.
+    fn get(&mut self, key: &Key, tensor: &Bound<PyAny>) -> Option<Tensor> {
+        self.tick += 1;
+
+        // Upgrading a weak reference doesn't run any Python code, so it is safe under the lock.
+        let entry = self.entries.get_mut(key).filter(|entry| {
+            entry
+                .source
+                .bind(tensor.py())
+                .upgrade()
+                .is_some_and(|source| source.is(tensor))
+        });
+
+        match entry {
+            Some(entry) => {
+                entry.last_used = self.tick;
+                self.stats.hits += 1;
+
+                Some(entry.tensor.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+
+                None
+            }
+        }
+    }
+
+    /// Make room for `bytes` more, returning the evicted entries so they are dropped outside the lock.
+    fn evict(&mut self, bytes: usize) -> Vec<Entry> {
+        let mut evicted = vec![];
+
+        while self.stats.bytes + bytes > self.budget {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+
+            let Some(entry) = oldest.and_then(|key| self.entries.remove(&key)) else {
+                break;
+            };
+
+            self.stats.bytes -= entry.bytes;
+            self.stats.evictions += 1;
+
+            evicted.push(entry);
+        }
+
+        self.stats.entries = self.entries.len();
+
+        evicted
+    }
+
+    fn insert(&mut self, key: Key, tensor: Tensor, source: Py<PyWeakrefReference>) -> Vec<Entry> {
+        let bytes = tensor.elem_count() * tensor.dtype().size_in_bytes();
+
+        if !self.is_enabled() || bytes > self.budget {
+            return vec![];
+        }
+
+        let mut evicted = self.evict(bytes);
+
+        self.tick += 1;
+        self.stats.bytes += bytes;
+
+        let entry = Entry {
+            tensor,
+            bytes,
+            last_used: self.tick,
+            source,
+        };
+
+        if let Some(previous) = self.entries.insert(key, entry) {
+            self.stats.bytes -= previous.bytes;
+
+            evicted.push(previous);
+        }
+
+        self.stats.entries = self.entries.len();
+
+        evicted
+    }
+}
+
+/// Enable the cache with a budget in bytes for the converted tensors, or disable it with `0`.
+///
+/// Entries that no longer fit into a smaller budget are evicted right away.
+pub fn configure(budget: usize) {
+    let evicted = {
+        let mut cache = TensorCache::lock();
+
+        cache.budget = budget;
+        cache.evict(0)
+    };
+
+    drop(evicted);
+}
+
+/// Drop every cached tensor, e.g. at the end of a workflow execution. The counters are kept.
+pub fn clear() {
+    let entries = TensorCache::lock().take_entries();
+
+    drop(entries);
+}
+
+/// Drop every cached tensor once ComfyUI moved on to another prompt.
+///
+/// Nodes call this before decoding their inputs, which keeps tensors cached while the prompt
+/// that produced them runs, but not any longer.
+pub fn enter_execution(python: Python) {
+    if !TensorCache::lock().is_enabled() {
+        return;
+    }
+
+    let execution = prompt_id(python);
+
+    let entries = {
+        let mut cache = TensorCache::lock();
+
+        match cache.execution == execution {
+            true => HashMap::new(),
+            false => {
+                cache.execution = execution;
+                cache.take_entries()
+            }
+        }
+    };
+
+    drop(entries);
+}
+
+/// The id of the prompt ComfyUI is executing, if running within ComfyUI.
+fn prompt_id(python: Python) -> Option<String> {
+    python
+        .import("server")
+        .and_then(|server| {
+            server
+                .getattr("PromptServer")?
+                .getattr("instance")?
+                .getattr("last_prompt_id")
+        })
+        .and_then(|prompt_id| prompt_id.extract())
+        .ok()
+}
+
+pub fn stats() -> CacheStats {
+    TensorCache::lock().stats
+}
+
+pub fn reset_stats() {
+    let mut cache = TensorCache::lock();
+
+    cache.stats = CacheStats {
+        entries: cache.entries.len(),
+        bytes: cache.stats.bytes,
+        ..CacheStats::default()
+    };
+}
+
+/// Look up the conversion of `tensor` to `dtype` on `device`, running `convert` on a miss.
+///
+/// Calls `convert` straight away while the cache is disabled. Otherwise the tensor returned is
+/// always a copy of the cached one, so changing it in place never reaches the cache.
+pub fn get_or_convert(
+    tensor: &Bound<PyAny>,
+    dtype: DType,
+    device: &Device,
+    convert: impl FnOnce() -> PyResult<Tensor>,
+) -> PyResult<Tensor> {
+    if !TensorCache::lock().is_enabled() {
+        return convert();
+    }
+
+    let key = Key::of(tensor, dtype, device)?;
+
+    let cached = TensorCache::lock().get(&key, tensor);
+
+    if let Some(cached) = cached {
+        return cached.copy().map_err(candle_error);
+    }
+
+    // The lock is not held while converting, as that calls into Python, which may switch threads.
+    let converted = convert()?;
+    let source = PyWeakrefReference::new(tensor)?.unbind();
+    let evicted = TensorCache::lock().insert(key, converted.copy().map_err(candle_error)?, source);
+
+    drop(evicted);
+
+    Ok(converted)
+}
+
+fn candle_error(error: candle_core::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("Execution failed: {}", error))
+}
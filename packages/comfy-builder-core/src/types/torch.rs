@@ -0,0 +1,426 @@
+use crate::types::dlpack;
+use candle_core::{DType, Device, Tensor as CandleTensor};
+use half::{bf16, f16};
+use numpy::{PyArray1, PyArrayMethods};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyTuple;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::thread::LocalKey;
+
+/// The torch dtypes that can cross the bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TorchDType {
+    UInt8,
+    UInt32,
+    Int64,
+    Float16,
+    BFloat16,
+    Float32,
+    Float64,
+    Float8E4M3Fn,
+    Float8E5M2,
+}
+
+impl TorchDType {
+    /// Parse a `torch.dtype`, e.g. `torch.bfloat16`.
+    pub fn from_torch(dtype: &Bound<PyAny>) -> PyResult<Self> {
+        let name = dtype.str()?.to_string();
+
+        Ok(match name.trim_start_matches("torch.") {
+            "uint8" => TorchDType::UInt8,
+            "uint32" => TorchDType::UInt32,
+            "int64" => TorchDType::Int64,
+            "float16" => TorchDType::Float16,
+            "bfloat16" => TorchDType::BFloat16,
+            "float32" => TorchDType::Float32,
+            "float64" => TorchDType::Float64,
+            "float8_e4m3fn" => TorchDType::Float8E4M3Fn,
+            "float8_e5m2" => TorchDType::Float8E5M2,
+            _ => return Err(PyTypeError::new_err(format!("unsupported tensor dtype `{}`", name))),
+        })
+    }
+
+    /// The name of the dtype within the `torch` module.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TorchDType::UInt8 => "uint8",
+            TorchDType::UInt32 => "uint32",
+            TorchDType::Int64 => "int64",
+            TorchDType::Float16 => "float16",
+            TorchDType::BFloat16 => "bfloat16",
+            TorchDType::Float32 => "float32",
+            TorchDType::Float64 => "float64",
+            TorchDType::Float8E4M3Fn => "float8_e4m3fn",
+            TorchDType::Float8E5M2 => "float8_e5m2",
+        }
+    }
+
+    /// The dtype the values are held in on the Rust side.
+    ///
+    /// Candle has no fp8 types, so those are widened to `F16`, which holds every fp8 value exactly.
+    pub fn candle_dtype(&self) -> DType {
+        match self {
+            TorchDType::UInt8 => DType::U8,
+            TorchDType::UInt32 => DType::U32,
+            TorchDType::Int64 => DType::I64,
+            TorchDType::Float16 | TorchDType::Float8E4M3Fn | TorchDType::Float8E5M2 => DType::F16,
+            TorchDType::BFloat16 => DType::BF16,
+            TorchDType::Float32 => DType::F32,
+            TorchDType::Float64 => DType::F64,
+        }
+    }
+
+    pub fn is_fp8(&self) -> bool {
+        matches!(self, TorchDType::Float8E4M3Fn | TorchDType::Float8E5M2)
+    }
+
+    fn resolve<'py>(&self, python: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        python.import("torch")?.getattr(self.name())
+    }
+}
+
+impl From<DType> for TorchDType {
+    fn from(dtype: DType) -> Self {
+        match dtype {
+            DType::U8 => TorchDType::UInt8,
+            DType::U32 => TorchDType::UInt32,
+            DType::I64 => TorchDType::Int64,
+            DType::F16 => TorchDType::Float16,
+            DType::BF16 => TorchDType::BFloat16,
+            DType::F32 => TorchDType::Float32,
+            DType::F64 => TorchDType::Float64,
+        }
+    }
+}
+
+impl Display for TorchDType {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "torch.{}", self.name())
+    }
+}
+
+/// How tensor inputs that do not match what a node declares are handled, set per field with
+/// `#[dtype_policy = "cast"]`, `#[dtype_policy = "strict"]` or `#[dtype_policy = "error"]`.
+///
+/// Fields without a policy are [`DTypePolicy::Strict`], so a tensor is never silently cast
+/// unless the field opts into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DTypePolicy {
+    /// Detach the tensor, move it to the cpu and cast it to the declared dtype.
+    Cast,
+    /// Detach the tensor and move it to the cpu, but reject any other dtype than the declared one.
+    #[default]
+    Strict,
+    /// Reject tensors that would need any conversion at all.
+    Error,
+}
+
+thread_local! {
+    static POLICY: Cell<DTypePolicy> = const { Cell::new(DTypePolicy::Strict) };
+    static OUTPUT_PLACEMENT: Cell<OutputPlacement> = const { Cell::new(OutputPlacement::Declared) };
+}
+
+/// Puts back the previous value of a thread local setting once a scope ends, even on panic.
+struct Restore<T: Copy + 'static>(&'static LocalKey<Cell<T>>, T);
+
+impl<T: Copy + 'static> Drop for Restore<T> {
+    fn drop(&mut self) {
+        self.0.set(self.1);
+    }
+}
+
+impl DTypePolicy {
+    /// The policy applied to tensors converted on this thread right now.
+    pub fn current() -> Self {
+        POLICY.get()
+    }
+
+    /// Apply this policy to every tensor converted while running `extract`.
+    pub fn scope<R>(self, extract: impl FnOnce() -> R) -> R {
+        let _restore = Restore(&POLICY, POLICY.replace(self));
+
+        extract()
+    }
+
+    /// Check a torch tensor against the dtype it is about to be converted to.
+    pub fn check(&self, tensor: &Bound<PyAny>, expected: TorchDType) -> PyResult<()> {
+        if *self == DTypePolicy::Cast {
+            return Ok(());
+        }
+
+        let actual = TorchDType::from_torch(&tensor.getattr("dtype")?)?;
+
+        if actual != expected {
+            return Err(PyTypeError::new_err(format!(
+                "expected a tensor of dtype `{}`, received `{}`",
+                expected, actual
+            )));
+        }
+
+        if *self == DTypePolicy::Error {
+            if tensor.getattr("requires_grad")?.extract::<bool>()? {
+                return Err(PyTypeError::new_err(
+                    "expected a detached tensor, received one that requires grad",
+                ));
+            }
+
+            let device = tensor.getattr("device")?;
+
+            if device.getattr("type")?.extract::<String>()? != "cpu" {
+                return Err(PyTypeError::new_err(format!(
+                    "expected a tensor on the cpu, received one on `{}`",
+                    device.str()?
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The device and dtype a tensor had on the torch side, remembered when decoding inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Placement {
+    device: String,
+    dtype: TorchDType,
+}
+
+impl Placement {
+    pub fn new(device: impl Into<String>, dtype: TorchDType) -> Self {
+        Self {
+            device: device.into(),
+            dtype,
+        }
+    }
+
+    /// Read the placement of a torch tensor, e.g. `cuda:0` and `torch.float16`.
+    pub fn of(tensor: &Bound<PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            device: tensor.getattr("device")?.str()?.to_string(),
+            dtype: TorchDType::from_torch(&tensor.getattr("dtype")?)?,
+        })
+    }
+
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    pub fn dtype(&self) -> TorchDType {
+        self.dtype
+    }
+
+    pub fn is_cpu(&self) -> bool {
+        self.device == "cpu"
+    }
+}
+
+/// Where output tensors are handed back to torch, set per node with `Node::OUTPUT_PLACEMENT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputPlacement {
+    /// On the cpu, with the dtype the output is declared with, e.g. `float32` for `Image<f32>`.
+    #[default]
+    Declared,
+    /// On the device and with the dtype of the input a value was decoded from, if there was one.
+    Source,
+}
+
+impl OutputPlacement {
+    /// The placement applied to tensors handed to torch on this thread right now.
+    pub fn current() -> Self {
+        OUTPUT_PLACEMENT.get()
+    }
+
+    /// Apply this placement to every tensor handed to torch while running `convert`.
+    pub fn scope<R>(self, convert: impl FnOnce() -> R) -> R {
+        let _restore = Restore(&OUTPUT_PLACEMENT, OUTPUT_PLACEMENT.replace(self));
+
+        convert()
+    }
+
+    /// Where a value declared as `declared`, which was decoded from `source`, ends up.
+    pub fn resolve(&self, declared: TorchDType, source: Option<&Placement>) -> Placement {
+        match (self, source) {
+            (OutputPlacement::Source, Some(source)) => source.clone(),
+            _ => Placement::new("cpu", declared),
+        }
+    }
+}
+
+fn candle_error(error: candle_core::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("Execution failed: {}", error))
+}
+
+fn native_bytes<T, const N: usize>(values: Vec<T>, to_bytes: fn(T) -> [u8; N]) -> Vec<u8> {
+    values.into_iter().flat_map(to_bytes).collect()
+}
+
+fn to_bytes(tensor: &CandleTensor) -> candle_core::Result<Vec<u8>> {
+    let tensor = tensor.flatten_all()?;
+
+    Ok(match tensor.dtype() {
+        DType::U8 => tensor.to_vec1::<u8>()?,
+        DType::U32 => native_bytes(tensor.to_vec1::<u32>()?, u32::to_ne_bytes),
+        DType::I64 => native_bytes(tensor.to_vec1::<i64>()?, i64::to_ne_bytes),
+        DType::F16 => native_bytes(tensor.to_vec1::<f16>()?, f16::to_ne_bytes),
+        DType::BF16 => native_bytes(tensor.to_vec1::<bf16>()?, bf16::to_ne_bytes),
+        DType::F32 => native_bytes(tensor.to_vec1::<f32>()?, f32::to_ne_bytes),
+        DType::F64 => native_bytes(tensor.to_vec1::<f64>()?, f64::to_ne_bytes),
+    })
+}
+
+/// Copy a torch tensor of any supported dtype into candle, keeping its dtype.
+///
+/// Contiguous cpu tensors are read straight from torch's memory through DLPack, anything else
+/// goes through [`copy_from_torch`].
+pub fn from_torch(tensor: Bound<PyAny>, device: &Device) -> PyResult<(CandleTensor, TorchDType)> {
+    let dtype = TorchDType::from_torch(&tensor.getattr("dtype")?)?;
+
+    if !dtype.is_fp8()
+        && let Some(tensor) = dlpack::from_dlpack(&tensor.call_method0("detach")?, device)?
+    {
+        return Ok((tensor, dtype));
+    }
+
+    copy_from_torch(tensor, device)
+}
+
+/// Copy a torch tensor into candle through numpy, moving the data around as raw bytes.
+///
+/// Slower than [`from_torch`], but works for every tensor: it is moved to the cpu and made
+/// contiguous first, and dtypes numpy has no equivalent for, such as `bfloat16` and the fp8
+/// variants, are supported as well.
+pub fn copy_from_torch(tensor: Bound<PyAny>, device: &Device) -> PyResult<(CandleTensor, TorchDType)> {
+    let python = tensor.py();
+    let dtype = TorchDType::from_torch(&tensor.getattr("dtype")?)?;
+    let shape = tensor.getattr("shape")?.extract::<Vec<usize>>()?;
+
+    let mut tensor = tensor.call_method0("detach")?.call_method0("cpu")?;
+
+    if dtype.is_fp8() {
+        tensor = tensor.call_method1("to", (TorchDType::Float16.resolve(python)?,))?;
+    }
+
+    let bytes = tensor
+        .call_method0("contiguous")?
+        .call_method1("reshape", (-1,))?
+        .call_method1("view", (TorchDType::UInt8.resolve(python)?,))?
+        .call_method0("numpy")?;
+
+    let bytes = bytes.downcast::<PyArray1<u8>>()?.readonly();
+
+    let tensor =
+        CandleTensor::from_raw_buffer(bytes.as_slice()?, dtype.candle_dtype(), &shape, device).map_err(candle_error)?;
+
+    Ok((tensor, dtype))
+}
+
+/// Hand a candle tensor to torch as `dtype`, casting the values if needed.
+///
+/// The values are copied once, through DLPack, into a buffer owned by the new torch tensor, so
+/// the two never share memory. Torch versions without `torch.from_dlpack` fall back to
+/// [`copy_into_torch`].
+pub fn into_torch<'py>(python: Python<'py>, tensor: &CandleTensor, dtype: TorchDType) -> PyResult<Bound<'py, PyAny>> {
+    let torch = python.import("torch")?;
+
+    if !torch.hasattr("from_dlpack")? {
+        return copy_into_torch(python, tensor, dtype);
+    }
+
+    let tensor = tensor.to_dtype(dtype.candle_dtype()).map_err(candle_error)?;
+    let tensor = torch.call_method1("from_dlpack", (dlpack::to_dlpack(python, &tensor)?,))?;
+
+    match dtype.is_fp8() {
+        true => tensor.call_method1("to", (dtype.resolve(python)?,)),
+        false => Ok(tensor),
+    }
+}
+
+/// Copy a candle tensor into a new torch tensor of `dtype` through numpy, casting the values if needed.
+pub fn copy_into_torch<'py>(
+    python: Python<'py>,
+    tensor: &CandleTensor,
+    dtype: TorchDType,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tensor = tensor.to_dtype(dtype.candle_dtype()).map_err(candle_error)?;
+    let shape = PyTuple::new(python, tensor.dims())?;
+    let bytes = PyArray1::from_vec(python, to_bytes(&tensor).map_err(candle_error)?);
+
+    let torch = python.import("torch")?;
+    let held = TorchDType::from(tensor.dtype());
+
+    let tensor = torch
+        .call_method1("from_numpy", (bytes,))?
+        .call_method1("view", (held.resolve(python)?,))?
+        .call_method1("reshape", (shape,))?;
+
+    match held == dtype {
+        true => Ok(tensor),
+        false => tensor.call_method1("to", (dtype.resolve(python)?,)),
+    }
+}
+
+/// Hand a candle tensor to torch on the device and with the dtype of `placement`.
+pub fn into_torch_at<'py>(
+    python: Python<'py>,
+    tensor: &CandleTensor,
+    placement: &Placement,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tensor = into_torch(python, tensor, placement.dtype)?;
+
+    match placement.is_cpu() {
+        true => Ok(tensor),
+        false => tensor.call_method1("to", (placement.device(),)),
+    }
+}
+
+/// A tensor that remembers the torch dtype it came from, so it is handed back to torch unchanged,
+/// e.g. fp8 model weights stay fp8 even though they are processed as `F16` in Rust.
+#[derive(Clone, Debug)]
+pub struct TorchTensor {
+    tensor: CandleTensor,
+    dtype: TorchDType,
+}
+
+impl TorchTensor {
+    pub fn new(tensor: CandleTensor, dtype: TorchDType) -> Self {
+        Self { tensor, dtype }
+    }
+
+    pub fn tensor(&self) -> &CandleTensor {
+        &self.tensor
+    }
+
+    pub fn into_tensor(self) -> CandleTensor {
+        self.tensor
+    }
+
+    pub fn dtype(&self) -> TorchDType {
+        self.dtype
+    }
+
+    /// Keep the original dtype while replacing the values, e.g. after processing them.
+    pub fn with_tensor(self, tensor: CandleTensor) -> Self {
+        Self { tensor, ..self }
+    }
+}
+
+impl<'py> FromPyObject<'py> for TorchTensor {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let (tensor, dtype) = from_torch(object.clone(), &Device::Cpu)?;
+
+        Ok(Self { tensor, dtype })
+    }
+}
+
+impl<'py> IntoPyObject<'py> for TorchTensor {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        into_torch(python, &self.tensor, self.dtype)
+    }
+}
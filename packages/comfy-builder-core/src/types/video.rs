@@ -0,0 +1,123 @@
+use crate::registry;
+use crate::types::audio::Audio;
+use crate::types::comfy_type::{AsInput, ComfyType};
+use crate::types::image::Image;
+use candle_core::{Device, WithDType};
+use numpy::Element;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::{Bound, FromPyObject, IntoPyObject, PyAny, PyErr, PyResult, Python};
+use std::time::Duration;
+
+/// ComfyUI `VIDEO`, decoded into its frames `[F, H, W, C]`, frame rate and optional audio track.
+#[derive(Clone, Debug)]
+pub struct Video<T: Element + WithDType = f32> {
+    frames: Image<T>,
+    frame_rate: f64,
+    audio: Option<Audio<T>>,
+}
+
+impl<'py, T: Element + WithDType> FromPyObject<'py> for Video<T> {
+    fn extract_bound(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Video::new(object.extract::<Bound<'py, PyAny>>()?)
+    }
+}
+
+impl<'py, T: Element + WithDType> AsInput<'py> for Video<T> {
+    fn comfy_type() -> ComfyType {
+        ComfyType::Video
+    }
+}
+
+impl<T: Element + WithDType> Video<T> {
+    pub fn new(any: Bound<PyAny>) -> PyResult<Self> {
+        let components = any.call_method0("get_components")?;
+
+        let frames = Image::<T>::new(components.getattr("images")?, &Device::Cpu)?;
+        let frame_rate = components.getattr("frame_rate")?.extract::<f64>()?;
+        let audio = components.getattr("audio")?;
+
+        let audio = match audio.is_none() {
+            true => None,
+            false => Some(Audio::<T>::new(audio)?),
+        };
+
+        Self::from_frames(frames, frame_rate, audio)
+            .map_err(|error| PyValueError::new_err(format!("invalid video: {}", error)))
+    }
+
+    pub fn from_frames(frames: Image<T>, frame_rate: f64, audio: Option<Audio<T>>) -> candle_core::Result<Self> {
+        frames.dims4()?;
+
+        Ok(Self {
+            frames,
+            frame_rate,
+            audio,
+        })
+    }
+
+    pub fn frames(&self) -> &Image<T> {
+        &self.frames
+    }
+
+    pub fn into_frames(self) -> Image<T> {
+        self.frames
+    }
+
+    pub fn frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    pub fn frame_count(&self) -> candle_core::Result<usize> {
+        self.frames.batch_size()
+    }
+
+    pub fn audio(&self) -> Option<&Audio<T>> {
+        self.audio.as_ref()
+    }
+
+    pub fn take_audio(&mut self) -> Option<Audio<T>> {
+        self.audio.take()
+    }
+
+    pub fn duration(&self) -> candle_core::Result<Duration> {
+        Ok(match self.frame_rate > 0.0 {
+            true => Duration::from_secs_f64(self.frame_count()? as f64 / self.frame_rate),
+            false => Duration::ZERO,
+        })
+    }
+}
+
+impl<'py, T: Element + WithDType> IntoPyObject<'py> for Video<T> {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, python: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let api = registry::api_module(python)?;
+        let frame_rate = python
+            .import("fractions")?
+            .getattr("Fraction")?
+            .call1((self.frame_rate,))?
+            .call_method1("limit_denominator", (1_000_000,))?;
+
+        let kwargs = PyDict::new(python);
+
+        kwargs.set_item("images", self.frames.into_pyobject(python)?)?;
+        kwargs.set_item(
+            "audio",
+            self.audio.map(|audio| audio.into_pyobject(python)).transpose()?,
+        )?;
+        kwargs.set_item("frame_rate", frame_rate)?;
+
+        let components = api
+            .getattr("Types")?
+            .getattr("VideoComponents")?
+            .call((), Some(&kwargs))?;
+
+        api.getattr("InputImpl")?
+            .getattr("VideoFromComponents")?
+            .call1((components,))
+    }
+}
@@ -0,0 +1,66 @@
+//!
+//! Verify that wildcard inputs accept and forward any value
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeInput, NodeOutput, node};
+use comfy_builder_core::types::any::Any;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    value: Any,
+    fallback: Option<Any>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    value: Any,
+    type_name: String,
+}
+
+#[node]
+struct Switch;
+
+impl Node for Switch {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Python::attach(|python| {
+            let value = match input.fallback {
+                Some(fallback) if input.value.bind(python).is_none() => fallback,
+                _ => input.value,
+            };
+
+            Ok(Output {
+                type_name: value.type_name(python)?,
+                value,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::run_node;
+
+    #[test]
+    pub fn test_any() {
+        Python::initialize();
+        Python::attach(|python| {
+            let output = run_node!(
+                Switch,
+                Input {
+                    value: Any::new(python.None()),
+                    fallback: Some(Any::new(42u32.into_pyobject(python).unwrap().into_any().unbind())),
+                }
+            );
+
+            assert_eq!(output.type_name, "int");
+            assert_eq!(output.value.extract::<u32>(python).unwrap(), 42);
+        });
+    }
+}
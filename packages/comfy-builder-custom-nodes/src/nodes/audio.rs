@@ -0,0 +1,78 @@
+//!
+//! Verify that audio waveforms can be processed in Rust
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Audio, Image, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    audio: Audio,
+    #[default = 1.0]
+    gain: f32,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    audio: Audio,
+}
+
+#[node]
+struct AudioGain;
+
+impl Node for AudioGain {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let sample_rate = input.audio.sample_rate();
+        let waveform = input
+            .audio
+            .into_waveform()
+            .into_tensor()
+            .affine(input.gain as f64, 0.0)?;
+
+        Ok(Output {
+            audio: Audio::from_waveform(Image::from_tensor(waveform), sample_rate)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_audio_gain() -> comfy_builder_core::candle::Result<()> {
+        let waveform = Tensor::ones((1, 2, 22050), DType::F32, &Device::Cpu)?;
+
+        let output = run_node!(
+            AudioGain,
+            Input {
+                audio: Audio::from_waveform(Image::from_tensor(waveform), 44100)?,
+                gain: 0.5,
+            }
+        );
+
+        assert_eq!(output.audio.sample_rate(), 44100);
+        assert_eq!(output.audio.channels(), 2);
+        assert_eq!(output.audio.duration(), Duration::from_millis(500));
+        assert_eq!(output.audio.waveform().max_all()?.to_scalar::<f32>()?, 0.5);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_audio_rejects_invalid_shape() -> comfy_builder_core::candle::Result<()> {
+        let waveform = Tensor::ones((2, 22050), DType::F32, &Device::Cpu)?;
+
+        assert!(Audio::<f32>::from_waveform(Image::from_tensor(waveform), 44100).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//!
+//! Verify that autogrow inputs collect every connected slot in order without enabling list mode
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeInput, NodeOutput, node};
+use comfy_builder_core::types::autogrow::Autogrow;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    #[min = 2]
+    #[max = 16]
+    strings: Autogrow<String>,
+    separator: String,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    string: String,
+}
+
+#[node]
+struct JoinStrings;
+
+impl Node for JoinStrings {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            string: input.strings.join(&input.separator),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::{In, Kwargs};
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyDict;
+
+    #[test]
+    pub fn test_autogrow() {
+        Python::initialize();
+        Python::attach(|python| {
+            let strings = PyDict::new(python);
+
+            strings.set_item("string10", "k").unwrap();
+            strings.set_item("string2", "c").unwrap();
+            strings.set_item("string0", "a").unwrap();
+            strings.set_item("string1", "b").unwrap();
+            strings.set_item("string3", python.None()).unwrap();
+
+            let kwargs = PyDict::new(python);
+
+            kwargs.set_item("strings", strings).unwrap();
+            kwargs.set_item("separator", "-").unwrap();
+
+            let output = run_node!(JoinStrings, Input::try_from(Kwargs(Some(kwargs))).unwrap());
+
+            assert!(!Input::is_list());
+            assert_eq!(output.string, "a-b-c-k");
+        });
+    }
+}
@@ -0,0 +1,134 @@
+//!
+//! Verify that conditioning entries and their metadata can be combined in Rust
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Conditioning, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    conditioning_a: Conditioning,
+    conditioning_b: Conditioning,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    conditioning: Conditioning,
+}
+
+#[node]
+struct ConditioningCombine;
+
+impl Node for ConditioningCombine {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let mut entries = input.conditioning_a.into_entries();
+
+        entries.extend(input.conditioning_b.into_entries());
+
+        Ok(Output {
+            conditioning: Conditioning::new(entries),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nodes::fixtures;
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+    use comfy_builder_core::types::conditioning::{ConditioningArea, ConditioningEntry, ConditioningMetadata};
+    use comfy_builder_core::types::torch::{TorchDType, into_torch};
+    use pyo3::prelude::*;
+    use pyo3::types::{PyDict, PyList};
+
+    #[test]
+    pub fn test_conditioning_combine() -> comfy_builder_core::candle::Result<()> {
+        let area = ConditioningArea::Latent {
+            height: 64,
+            width: 64,
+            y: 0,
+            x: 8,
+        };
+
+        let entry = ConditioningEntry::new(
+            Tensor::zeros((1, 77, 768), DType::F32, &Device::Cpu)?,
+            ConditioningMetadata {
+                area: Some(area),
+                strength: Some(0.5),
+                ..Default::default()
+            },
+        );
+
+        let output = run_node!(
+            ConditioningCombine,
+            Input {
+                conditioning_a: Conditioning::new(vec![entry]),
+                conditioning_b: Conditioning::new(vec![Tensor::ones((1, 77, 768), DType::F32, &Device::Cpu)?.into()]),
+            }
+        );
+
+        let entries = output.conditioning.entries();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].metadata.area, Some(area));
+        assert_eq!(entries[0].metadata.strength, Some(0.5));
+        assert_eq!(entries[1].metadata.strength, None);
+        assert_eq!(entries[1].embeddings.dims(), &[1, 77, 768]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_conditioning_floats_round_trip() {
+        let _serial = fixtures::serial();
+
+        Python::initialize();
+        Python::attach(|python| {
+            let _torch = fixtures::torch(python);
+
+            let embeddings = Tensor::zeros((1, 2, 2), DType::F32, &Device::Cpu).unwrap();
+            let embeddings = into_torch(python, &embeddings, TorchDType::Float32).unwrap();
+            let metadata = PyDict::new(python);
+
+            metadata.set_item("strength", 0.1).unwrap();
+            metadata.set_item("end_percent", 0.1).unwrap();
+            metadata.set_item("area", ("percentage", 0.1, 0.2, 0.3, 0.7)).unwrap();
+
+            let entry = PyList::new(python, [embeddings, metadata.into_any()]).unwrap();
+            let conditioning = PyList::new(python, [entry]).unwrap().extract::<Conditioning>().unwrap();
+
+            let metadata = &conditioning.entries()[0].metadata;
+
+            assert_eq!(metadata.strength, Some(0.1));
+            assert_eq!(metadata.end_percent, Some(0.1));
+            assert_eq!(
+                metadata.area,
+                Some(ConditioningArea::Percentage {
+                    height: 0.1,
+                    width: 0.2,
+                    y: 0.3,
+                    x: 0.7,
+                })
+            );
+
+            let output = conditioning.into_pyobject(python).unwrap();
+            let metadata = output.get_item(0).unwrap().get_item(1).unwrap();
+
+            assert!(metadata.get_item("strength").unwrap().eq(0.1).unwrap());
+            assert!(metadata.get_item("end_percent").unwrap().eq(0.1).unwrap());
+            assert!(
+                metadata
+                    .get_item("area")
+                    .unwrap()
+                    .eq(("percentage", 0.1, 0.2, 0.3, 0.7))
+                    .unwrap()
+            );
+        });
+    }
+}
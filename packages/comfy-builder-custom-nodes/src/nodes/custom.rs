@@ -12,12 +12,20 @@ use std::error::Error;
 pub struct Input {
     seed: Seed<u8>,
     slider: Slider<u16>,
+    #[min = 0.0]
+    #[max = 1.0]
+    #[step = 0.05]
+    #[round = 0.05]
+    slider_float: Slider<f32>,
+    seed_float: Seed<f64>,
 }
 
 #[derive(NodeOutput)]
 pub struct Output {
     seed: u8,
     slider: u16,
+    slider_float: f32,
+    seed_float: f64,
 }
 
 #[node]
@@ -32,6 +40,8 @@ impl Node for Custom {
         Ok(Output {
             seed: *input.seed,
             slider: *input.slider,
+            slider_float: *input.slider_float,
+            seed_float: *input.seed_float,
         })
     }
 }
@@ -39,7 +49,9 @@ impl Node for Custom {
 #[cfg(test)]
 mod test {
     use super::*;
+    use comfy_builder_core::prelude::{AsInput, ComfyType};
     use comfy_builder_core::run_node;
+    use comfy_builder_core::types::int::{DEFAULT_FLOAT_STEP, numeric_options};
 
     #[test]
     pub fn test_custom_types() {
@@ -48,10 +60,50 @@ mod test {
             Input {
                 slider: Slider::new(1),
                 seed: Seed::new(1),
+                slider_float: Slider::new(0.25),
+                seed_float: Seed::new(0.5),
             }
         );
 
         assert_eq!(output.slider, 1u16);
         assert_eq!(output.seed, 1u8);
+        assert_eq!(output.slider_float, 0.25f32);
+        assert_eq!(output.seed_float, 0.5f64);
+    }
+
+    #[test]
+    pub fn test_numeric_widget_types() {
+        assert_eq!(Slider::<u16>::comfy_type(), ComfyType::Int);
+        assert_eq!(Slider::<f32>::comfy_type(), ComfyType::Float);
+        assert_eq!(Seed::<u64>::comfy_type(), ComfyType::Int);
+        assert_eq!(Seed::<f64>::comfy_type(), ComfyType::Float);
+    }
+
+    #[test]
+    pub fn test_float_step_default() {
+        Python::initialize();
+        Python::attach(|python| {
+            let dict = PyDict::new(python);
+
+            numeric_options::<f32>(&dict).unwrap();
+
+            assert_eq!(
+                dict.get_item("step").unwrap().unwrap().extract::<f64>().unwrap(),
+                DEFAULT_FLOAT_STEP
+            );
+
+            let dict = PyDict::new(python);
+
+            dict.set_item("step", 0.5).unwrap();
+            numeric_options::<f32>(&dict).unwrap();
+
+            assert_eq!(dict.get_item("step").unwrap().unwrap().extract::<f64>().unwrap(), 0.5);
+
+            let dict = PyDict::new(python);
+
+            numeric_options::<u32>(&dict).unwrap();
+
+            assert!(dict.get_item("step").unwrap().is_none());
+        });
     }
 }
@@ -0,0 +1,88 @@
+//!
+//! Verify that user-defined structs can be used as their own socket types
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{ComfyCustomType, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[pyo3::pyclass]
+#[derive(ComfyCustomType, Clone, Debug, PartialEq)]
+pub struct Skeleton {
+    joints: Vec<(f32, f32)>,
+}
+
+#[pyo3::pyclass]
+#[derive(ComfyCustomType, Clone, Debug, PartialEq)]
+#[io_type = "TRACKING_RESULT"]
+pub struct Tracking {
+    frames: usize,
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    skeleton: Skeleton,
+    tracking: Option<Tracking>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    skeleton: Skeleton,
+    tracking: Option<Tracking>,
+}
+
+#[node]
+struct CustomType;
+
+impl Node for CustomType {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            skeleton: input.skeleton,
+            tracking: input.tracking,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::{AsInput, ComfyType};
+    use comfy_builder_core::registry::CustomTypeRegistration;
+    use comfy_builder_core::run_node;
+
+    #[test]
+    pub fn test_custom_type() {
+        let skeleton = Skeleton {
+            joints: vec![(0.0, 1.0), (0.5, 0.5)],
+        };
+
+        let output = run_node!(
+            CustomType,
+            Input {
+                skeleton: skeleton.clone(),
+                tracking: Some(Tracking { frames: 12 }),
+            }
+        );
+
+        assert_eq!(output.skeleton, skeleton);
+        assert_eq!(output.tracking, Some(Tracking { frames: 12 }));
+    }
+
+    #[test]
+    pub fn test_custom_types_are_distinct() {
+        assert_eq!(Skeleton::comfy_type(), ComfyType::Custom("SKELETON"));
+        assert_eq!(Tracking::comfy_type(), ComfyType::Custom("TRACKING_RESULT"));
+        assert!(Skeleton::comfy_type() != Tracking::comfy_type());
+
+        let registered: Vec<_> = inventory::iter::<CustomTypeRegistration>()
+            .map(|registration| registration.name)
+            .collect();
+
+        assert!(registered.contains(&"SKELETON"));
+        assert!(registered.contains(&"TRACKING_RESULT"));
+    }
+}
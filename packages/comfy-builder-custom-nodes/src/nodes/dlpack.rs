@@ -0,0 +1,139 @@
+//!
+//! Verify that tensors can be exchanged through DLPack capsules
+//!
+
+use comfy_builder_core::candle::Device;
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, node};
+use comfy_builder_core::types::dlpack::{from_dlpack, to_dlpack};
+use pyo3::Python;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    image: Image<f32>,
+}
+
+#[node]
+struct FirstFrameThroughDlpack;
+
+impl Node for FirstFrameThroughDlpack {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let frame = input.image.narrow(0, 1, 1)?;
+
+        let tensor = Python::attach(|python| {
+            let capsule = to_dlpack(python, &frame)?;
+
+            from_dlpack(capsule.as_any(), &Device::Cpu)
+        })?;
+
+        Ok(Output {
+            image: Image::from_tensor(tensor.ok_or("the frame could not be read back")?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Tensor};
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyDict;
+
+    #[test]
+    pub fn test_dlpack_round_trip() -> comfy_builder_core::candle::Result<()> {
+        Python::initialize();
+
+        let image = Tensor::arange(0f32, 12f32, &Device::Cpu)?.reshape((2, 2, 1, 3))?;
+        let output = run_node!(
+            FirstFrameThroughDlpack,
+            Input {
+                image: Image::from_tensor(image),
+            }
+        );
+
+        assert_eq!(output.image.dims(), &[1, 2, 1, 3]);
+        assert_eq!(
+            output.image.flatten_all()?.to_vec1::<f32>()?,
+            vec![6.0, 7.0, 8.0, 9.0, 10.0, 11.0]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_dlpack_dtypes_and_layouts() {
+        Python::initialize();
+        Python::attach(|python| {
+            let tensor = Tensor::new(&[[1.0f32, 2.0], [3.0, 4.0]], &Device::Cpu)
+                .unwrap()
+                .to_dtype(DType::BF16)
+                .unwrap();
+
+            let capsule = to_dlpack(python, &tensor).unwrap();
+            let shared = from_dlpack(capsule.as_any(), &Device::Cpu).unwrap().unwrap();
+
+            assert_eq!(shared.dtype(), DType::BF16);
+            assert_eq!(
+                shared.to_dtype(DType::F32).unwrap().to_vec2::<f32>().unwrap(),
+                vec![vec![1.0, 2.0], vec![3.0, 4.0]]
+            );
+
+            // Strided tensors are copied into a contiguous buffer on the way out.
+            let transposed = to_dlpack(python, &tensor.t().unwrap()).unwrap();
+            let transposed = from_dlpack(transposed.as_any(), &Device::Cpu).unwrap().unwrap();
+
+            assert_eq!(
+                transposed.to_dtype(DType::F32).unwrap().to_vec2::<f32>().unwrap(),
+                vec![vec![1.0, 3.0], vec![2.0, 4.0]]
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_dlpack_capsule_owns_its_data() {
+        Python::initialize();
+        Python::attach(|python| {
+            let tensor = Tensor::new(&[1.0f32, 2.0, 3.0, 4.0], &Device::Cpu).unwrap();
+            let capsule = to_dlpack(python, &tensor).unwrap();
+            let locals = PyDict::new(python);
+
+            locals.set_item("capsule", &capsule).unwrap();
+
+            // Write through the data pointer of the capsule, like an in-place op on the torch side.
+            python
+                .run(
+                    c"
+import ctypes
+
+get_pointer = ctypes.pythonapi.PyCapsule_GetPointer
+get_pointer.restype = ctypes.c_void_p
+get_pointer.argtypes = [ctypes.py_object, ctypes.c_char_p]
+
+data = ctypes.c_void_p.from_address(get_pointer(capsule, b'dltensor')).value
+values = (ctypes.c_float * 4).from_address(data)
+
+for index in range(4):
+    values[index] *= 2
+",
+                    None,
+                    Some(&locals),
+                )
+                .unwrap();
+
+            let mutated = from_dlpack(capsule.as_any(), &Device::Cpu).unwrap().unwrap();
+
+            assert_eq!(mutated.to_vec1::<f32>().unwrap(), vec![2.0, 4.0, 6.0, 8.0]);
+            assert_eq!(tensor.to_vec1::<f32>().unwrap(), vec![1.0, 2.0, 3.0, 4.0]);
+        });
+    }
+}
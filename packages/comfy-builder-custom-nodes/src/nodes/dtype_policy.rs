@@ -0,0 +1,113 @@
+//!
+//! Verify that tensor inputs are checked against the dtype policy of their field
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, Mask, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+
+    #[dtype_policy = "error"]
+    mask: Option<Mask<f32>>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    image: Image<f32>,
+}
+
+#[node]
+struct ApplyMaskStrictly;
+
+impl Node for ApplyMaskStrictly {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let image = match input.mask {
+            Some(mask) => Image::from_tensor(input.image.broadcast_mul(&mask.unsqueeze(3)?)?),
+            None => input.image,
+        };
+
+        Ok(Output { image })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nodes::fixtures;
+    use comfy_builder_core::prelude::Kwargs;
+    use comfy_builder_core::types::torch::{DTypePolicy, TorchDType};
+    use pyo3::prelude::*;
+    use pyo3::types::{PyDict, PyModule};
+
+    /// A stand-in tensor, with the metadata the policies look at set up.
+    fn tensor<'py>(torch: &Bound<'py, PyModule>, dtype: &str, requires_grad: bool, device: &str) -> Bound<'py, PyAny> {
+        let tensor = fixtures::tensor(torch, dtype, &[0.0], &[1]);
+
+        tensor.setattr("requires_grad", requires_grad).unwrap();
+        tensor
+            .setattr("device", torch.call_method1("device", (device,)).unwrap())
+            .unwrap();
+        tensor
+    }
+
+    #[test]
+    pub fn test_strict_by_default() {
+        let _serial = fixtures::serial();
+
+        Python::initialize();
+        Python::attach(|python| {
+            let torch = fixtures::torch(python);
+            let kwargs = PyDict::new(python);
+
+            kwargs
+                .set_item("image", tensor(&torch, "float16", false, "cpu"))
+                .unwrap();
+
+            let error = Input::try_from(Kwargs(Some(kwargs))).err().unwrap().to_string();
+
+            assert_eq!(
+                error,
+                "ValueError: invalid value for input `image`: TypeError: expected a tensor of dtype \
+                 `torch.float32`, received `torch.float16`"
+            );
+            assert_eq!(DTypePolicy::current(), DTypePolicy::Strict);
+        });
+    }
+
+    #[test]
+    pub fn test_policies() {
+        let _serial = fixtures::serial();
+
+        Python::initialize();
+        Python::attach(|python| {
+            let torch = fixtures::torch(python);
+            let half = tensor(&torch, "float16", false, "cpu");
+            let attached = tensor(&torch, "float32", true, "cpu");
+            let gpu = tensor(&torch, "float32", false, "cuda");
+
+            assert!(DTypePolicy::Cast.check(&half, TorchDType::Float32).is_ok());
+            assert!(DTypePolicy::Strict.check(&half, TorchDType::Float32).is_err());
+            assert!(DTypePolicy::Strict.check(&attached, TorchDType::Float32).is_ok());
+            assert!(DTypePolicy::Strict.check(&gpu, TorchDType::Float32).is_ok());
+
+            let error = DTypePolicy::Error.check(&attached, TorchDType::Float32).unwrap_err();
+
+            assert!(error.to_string().contains("requires grad"));
+
+            let error = DTypePolicy::Error.check(&gpu, TorchDType::Float32).unwrap_err();
+
+            assert!(error.to_string().contains("`cuda`"));
+
+            DTypePolicy::Error.scope(|| assert_eq!(DTypePolicy::current(), DTypePolicy::Error));
+
+            assert_eq!(DTypePolicy::current(), DTypePolicy::Strict);
+        });
+    }
+}
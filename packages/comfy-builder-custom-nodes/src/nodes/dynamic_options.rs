@@ -0,0 +1,80 @@
+//!
+//! Verify that combo options can be computed at runtime and are validated on execution
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{DynamicOptions, NodeInput, NodeOutput, node};
+use std::error::Error;
+use std::sync::Mutex;
+
+static PRESETS: Mutex<Vec<&str>> = Mutex::new(vec![]);
+
+#[derive(DynamicOptions, Debug, PartialEq)]
+pub struct Preset(String);
+
+impl DynamicOptions for Preset {
+    fn options() -> Vec<String> {
+        PRESETS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|preset| preset.to_string())
+            .collect()
+    }
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    preset: Preset,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    preset: Preset,
+}
+
+#[node]
+struct PresetSelect;
+
+impl Node for PresetSelect {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output { preset: input.preset })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::Kwargs;
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyDict;
+
+    #[test]
+    pub fn test_dynamic_options() {
+        *PRESETS.lock().unwrap() = vec!["portrait", "landscape"];
+
+        Python::initialize();
+        Python::attach(|python| {
+            let kwargs = PyDict::new(python);
+
+            kwargs.set_item("preset", "landscape").unwrap();
+
+            let input = Input::try_from(Kwargs(Some(kwargs.clone()))).unwrap();
+            let output = run_node!(PresetSelect, input);
+
+            assert_eq!(output.preset, Preset("landscape".to_string()));
+
+            // The option disappears between the schema being defined and the node executing.
+            *PRESETS.lock().unwrap() = vec!["portrait"];
+
+            let error = Input::try_from(Kwargs(Some(kwargs))).err().unwrap().to_string();
+
+            assert!(error.contains("`preset`"));
+            assert!(error.contains("`landscape` is not one of the available options: [portrait]"));
+        });
+    }
+}
@@ -0,0 +1,283 @@
+//!
+//! Stand-ins shared by the tests of the example nodes
+//!
+
+use pyo3::prelude::*;
+use pyo3::types::PyModule;
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Run tests that touch process-wide state, such as `sys.modules` or the tensor cache, one at a time.
+///
+/// Take this before attaching to Python, as waiting for it while holding the GIL could deadlock.
+pub fn serial() -> MutexGuard<'static, ()> {
+    SERIAL.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A module installed into `sys.modules`, putting back whatever was there before once dropped.
+pub struct ModuleGuard<'py> {
+    module: Bound<'py, PyModule>,
+    name: String,
+    previous: Option<Bound<'py, PyAny>>,
+}
+
+impl<'py> ModuleGuard<'py> {
+    pub fn install(name: &str, module: Bound<'py, PyModule>) -> Self {
+        let modules = sys_modules(module.py());
+        let previous = modules.get_item(name).ok();
+
+        modules.set_item(name, &module).unwrap();
+
+        Self {
+            module,
+            name: name.to_string(),
+            previous,
+        }
+    }
+}
+
+impl<'py> Deref for ModuleGuard<'py> {
+    type Target = Bound<'py, PyModule>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.module
+    }
+}
+
+impl Drop for ModuleGuard<'_> {
+    fn drop(&mut self) {
+        let modules = sys_modules(self.module.py());
+
+        let _ = match &self.previous {
+            Some(previous) => modules.set_item(&self.name, previous),
+            None => modules.del_item(&self.name),
+        };
+    }
+}
+
+fn sys_modules(python: Python) -> Bound<PyAny> {
+    python.import("sys").unwrap().getattr("modules").unwrap()
+}
+
+/// Install a stand-in for the `torch` module, which holds tensors as raw bytes.
+///
+/// It converts between dtypes, fp8 included, and exchanges tensors through DLPack like torch does.
+/// Only `from_numpy` and `Tensor.numpy` need numpy to be installed.
+pub fn torch(python: Python) -> ModuleGuard {
+    let module = PyModule::from_code(python, TORCH, c"torch_stand_in.py", c"torch_stand_in").unwrap();
+
+    ModuleGuard::install("torch", module)
+}
+
+/// A tensor of the stand-in `torch` module, e.g. `tensor(&torch, "float16", &[1.0, 0.5], &[2])`.
+pub fn tensor<'py>(torch: &Bound<'py, PyModule>, dtype: &str, values: &[f64], shape: &[usize]) -> Bound<'py, PyAny> {
+    torch
+        .call_method1("tensor", (values, torch.getattr(dtype).unwrap(), shape))
+        .unwrap()
+}
+
+/// Whether numpy can be imported, which the copying conversions need.
+pub fn has_numpy(python: Python) -> bool {
+    python.import("numpy").is_ok()
+}
+
+const TORCH: &std::ffi::CStr = c"
+import ctypes
+import math
+import struct
+
+
+class device:
+    def __init__(self, type):
+        self.type = type
+
+    def __str__(self):
+        return self.type
+
+
+class dtype:
+    def __init__(self, name, itemsize, decode, encode):
+        self.name = name
+        self.itemsize = itemsize
+        self.decode = decode
+        self.encode = encode
+
+    def __str__(self):
+        return 'torch.' + self.name
+
+
+def _packed(name, format):
+    decode = lambda data: [value for (value,) in struct.iter_unpack('=' + format, data)]
+    encode = lambda values: struct.pack('=%d%s' % (len(values), format), *values)
+
+    return dtype(name, struct.calcsize('=' + format), decode, encode)
+
+
+def _bfloat16():
+    def decode(data):
+        return [struct.unpack('=f', struct.pack('=I', bits << 16))[0] for (bits,) in struct.iter_unpack('=H', data)]
+
+    def encode(values):
+        bits = [struct.unpack('=I', struct.pack('=f', value))[0] for value in values]
+
+        return struct.pack('=%dH' % len(bits), *((word + 0x7FFF + ((word >> 16) & 1)) >> 16 for word in bits))
+
+    return dtype('bfloat16', 2, decode, encode)
+
+
+def _float8(name, exponent, mantissa, finite):
+    bias = (1 << (exponent - 1)) - 1
+    table = []
+
+    for code in range(256):
+        sign = -1.0 if code & 0x80 else 1.0
+        biased = (code >> mantissa) & ((1 << exponent) - 1)
+        fraction = (code & ((1 << mantissa) - 1)) / (1 << mantissa)
+
+        if finite and code & 0x7F == 0x7F:
+            table.append(math.nan)
+        elif not finite and biased == (1 << exponent) - 1:
+            table.append(sign * math.inf if fraction == 0 else math.nan)
+        elif biased == 0:
+            table.append(sign * fraction * 2.0 ** (1 - bias))
+        else:
+            table.append(sign * (1 + fraction) * 2.0 ** (biased - bias))
+
+    codes = [code for code in range(256) if math.isfinite(table[code])]
+
+    def encode(values):
+        # Round to the nearest value, ties to the even code.
+        return bytes(min(codes, key=lambda code: (abs(table[code] - value), code & 1)) for value in values)
+
+    return dtype(name, 1, lambda data: [table[code] for code in data], encode)
+
+
+uint8 = _packed('uint8', 'B')
+uint32 = _packed('uint32', 'I')
+int64 = _packed('int64', 'q')
+float16 = _packed('float16', 'e')
+bfloat16 = _bfloat16()
+float32 = _packed('float32', 'f')
+float64 = _packed('float64', 'd')
+float8_e4m3fn = _float8('float8_e4m3fn', 4, 3, True)
+float8_e5m2 = _float8('float8_e5m2', 5, 2, False)
+
+
+class Tensor:
+    def __init__(self, data, dtype, shape):
+        self._data = bytes(data)
+        self._data_ptr = id(self)
+        self._version = 0
+        self.dtype = dtype
+        self.shape = tuple(shape)
+        self.device = device('cpu')
+        self.requires_grad = False
+
+    def data_ptr(self):
+        return self._data_ptr
+
+    def stride(self):
+        strides, step = [], 1
+
+        for size in reversed(self.shape):
+            strides.insert(0, step)
+            step *= size
+
+        return tuple(strides)
+
+    def detach(self):
+        return self
+
+    def cpu(self):
+        return self
+
+    def contiguous(self):
+        return self
+
+    def reshape(self, *shape):
+        if len(shape) == 1 and isinstance(shape[0], tuple):
+            shape = shape[0]
+
+        count = len(self._data) // self.dtype.itemsize
+        known = math.prod(size for size in shape if size != -1)
+
+        return Tensor(self._data, self.dtype, [count // known if size == -1 else size for size in shape])
+
+    def view(self, dtype):
+        shape = list(self.shape)
+        shape[-1] = shape[-1] * self.dtype.itemsize // dtype.itemsize
+
+        return Tensor(self._data, dtype, shape)
+
+    def to(self, dtype):
+        return Tensor(dtype.encode(self.dtype.decode(self._data)), dtype, self.shape)
+
+    def tolist(self):
+        return self.dtype.decode(self._data)
+
+    def numpy(self):
+        import numpy
+
+        return numpy.frombuffer(self._data, dtype=numpy.uint8).copy()
+
+
+class _DLTensor(ctypes.Structure):
+    _fields_ = [
+        ('data', ctypes.c_void_p),
+        ('device_type', ctypes.c_int32),
+        ('device_id', ctypes.c_int32),
+        ('ndim', ctypes.c_int32),
+        ('code', ctypes.c_uint8),
+        ('bits', ctypes.c_uint8),
+        ('lanes', ctypes.c_uint16),
+        ('shape', ctypes.POINTER(ctypes.c_int64)),
+        ('strides', ctypes.POINTER(ctypes.c_int64)),
+        ('byte_offset', ctypes.c_uint64),
+    ]
+
+
+_DLPACK_DTYPES = {
+    (1, 8): uint8,
+    (1, 32): uint32,
+    (0, 64): int64,
+    (2, 16): float16,
+    (4, 16): bfloat16,
+    (2, 32): float32,
+    (2, 64): float64,
+}
+
+
+class _DLPackTensor(Tensor):
+    def __init__(self, capsule):
+        get_pointer = ctypes.pythonapi.PyCapsule_GetPointer
+        get_pointer.restype = ctypes.c_void_p
+        get_pointer.argtypes = [ctypes.py_object, ctypes.c_char_p]
+
+        tensor = _DLTensor.from_address(get_pointer(capsule, b'dltensor'))
+        dtype = _DLPACK_DTYPES[(tensor.code, tensor.bits)]
+        shape = [tensor.shape[index] for index in range(tensor.ndim)]
+        data = ctypes.string_at(tensor.data + tensor.byte_offset, math.prod(shape) * dtype.itemsize)
+
+        super().__init__(data, dtype, shape)
+        self._capsule = capsule
+
+    def __dlpack__(self):
+        return self._capsule
+
+    def __dlpack_device__(self):
+        return (1, 0)
+
+
+def from_dlpack(capsule):
+    return _DLPackTensor(capsule)
+
+
+def from_numpy(array):
+    return Tensor(array.tobytes(), globals()[array.dtype.name], array.shape)
+
+
+def tensor(values, dtype, shape):
+    return Tensor(dtype.encode(values), dtype, shape)
+";
@@ -0,0 +1,103 @@
+//!
+//! Verify that color, point and bounding box inputs can be applied to images
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, node};
+use comfy_builder_core::types::bounding_box::BoundingBox;
+use comfy_builder_core::types::color::Color;
+use comfy_builder_core::types::point::Point;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+    region: BoundingBox,
+    anchor: Point,
+    #[default = "#ff0000"]
+    color: Color,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    filled: Image<f32>,
+    cropped: Image<f32>,
+    inside: bool,
+}
+
+#[node]
+struct FillRegion;
+
+impl Node for FillRegion {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let filled = input.image.fill_box(&input.region, input.color)?;
+
+        Ok(Output {
+            cropped: filled.crop(&input.region)?,
+            inside: input.region.contains(input.anchor),
+            filled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+
+    #[test]
+    pub fn test_fill_region() -> comfy_builder_core::candle::Result<()> {
+        let image = Tensor::zeros((2, 8, 8, 3), DType::F32, &Device::Cpu)?;
+
+        let output = run_node!(
+            FillRegion,
+            Input {
+                image: Image::from_tensor(image),
+                region: BoundingBox::new(2, 4, 3, 10),
+                anchor: Point::new(3, 5),
+                color: Color::rgb(255, 0, 0),
+            }
+        );
+
+        assert!(output.inside);
+        assert_eq!(output.cropped.dims(), &[2, 4, 3, 3]);
+        assert_eq!(output.filled.dims(), &[2, 8, 8, 3]);
+        assert_eq!(output.filled.sum_all()?.to_scalar::<f32>()?, 2.0 * 4.0 * 3.0);
+        assert_eq!(
+            output.cropped.get(1)?.get(0)?.get(0)?.to_vec1::<f32>()?,
+            vec![1.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            output.filled.get(0)?.get(0)?.get(0)?.to_vec1::<f32>()?,
+            vec![0.0, 0.0, 0.0]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_color_hex_round_trip() {
+        assert_eq!(Color::from_hex("#ff8000").unwrap(), Color::rgb(255, 128, 0));
+        assert_eq!(Color::from_hex("0f0").unwrap(), Color::rgb(0, 255, 0));
+        assert_eq!(Color::from_hex("#11223344").unwrap(), Color::rgba(17, 34, 51, 68));
+        assert_eq!(Color::rgb(255, 128, 0).to_hex(), "#ff8000");
+        assert_eq!(Color::rgba(17, 34, 51, 68).to_string(), "#11223344");
+        assert!(Color::from_hex("#12345").is_err());
+        assert!(Color::from_hex("#gggggg").is_err());
+    }
+
+    #[test]
+    pub fn test_bounding_box_saturates_at_the_edge() {
+        let region = BoundingBox::new(usize::MAX - 1, usize::MAX - 1, 10, 10);
+
+        assert_eq!(region.bottom_right(), Point::new(usize::MAX, usize::MAX));
+        assert!(region.contains(Point::new(usize::MAX - 1, usize::MAX - 1)));
+        assert!(!region.contains(Point::new(0, 0)));
+        assert_eq!(region.clamp(8, 8), BoundingBox::new(8, 8, 0, 0));
+    }
+}
@@ -0,0 +1,111 @@
+//!
+//! Verify that classifier-free guidance written in Rust can be handed to ComfyUI as a `GUIDER`
+//!
+
+use comfy_builder_core::candle::Tensor;
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Conditioning, Guider, Model, NodeInput, NodeOutput, node};
+use comfy_builder_core::types::guider::{ConditionedModel, GuiderFunction};
+use comfy_builder_core::types::sampler::SamplingError;
+use std::error::Error;
+
+pub struct Cfg {
+    scale: f64,
+}
+
+impl GuiderFunction for Cfg {
+    fn predict(&self, model: &dyn ConditionedModel, x: &Tensor, _: f64) -> Result<Tensor, SamplingError> {
+        let predictions = model.predict(x, &["positive", "negative"])?;
+        let (positive, negative) = (&predictions[0], &predictions[1]);
+
+        Ok((negative + ((positive - negative)? * self.scale)?)?)
+    }
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    model: Model,
+    positive: Conditioning,
+    negative: Conditioning,
+    #[min = 0.0]
+    #[max = 100.0]
+    #[step = 0.1]
+    cfg: f64,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    guider: Guider,
+}
+
+#[node]
+struct RustCfgGuider;
+
+impl Node for RustCfgGuider {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let guider = Guider::new(input.model, Cfg { scale: input.cfg })
+            .with_conds("positive", input.positive)
+            .with_conds("negative", input.negative);
+
+        Ok(Output { guider })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Device};
+    use comfy_builder_core::run_node;
+    use pyo3::Python;
+    use pyo3::types::PyString;
+
+    /// Predicts a constant value for each conditioning, `1.0` for positive and `0.5` for negative.
+    struct Constant;
+
+    impl ConditionedModel for Constant {
+        fn predict(&self, x: &Tensor, conds: &[&str]) -> Result<Vec<Tensor>, SamplingError> {
+            conds
+                .iter()
+                .map(|name| match *name {
+                    "positive" => Ok(x.ones_like()?),
+                    _ => Ok((x.ones_like()? * 0.5)?),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    pub fn test_cfg_guider() -> Result<(), SamplingError> {
+        let x = Tensor::zeros((1, 4, 8, 8), DType::F32, &Device::Cpu)?;
+        let output = Cfg { scale: 3.0 }.predict(&Constant, &x, 1.0)?;
+        let values = output.flatten_all()?.to_vec1::<f32>()?;
+
+        assert!(values.iter().all(|value| (value - 2.0).abs() < 1e-6));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_guider_node() {
+        Python::initialize();
+        Python::attach(|python| {
+            let model = Model::new(PyString::new(python, "model").into_any().unbind());
+            let output = run_node!(
+                RustCfgGuider,
+                Input {
+                    model,
+                    positive: Conditioning::new(vec![]),
+                    negative: Conditioning::new(vec![]),
+                    cfg: 7.0,
+                },
+                return
+            );
+
+            assert!(output.is_ok());
+        });
+    }
+}
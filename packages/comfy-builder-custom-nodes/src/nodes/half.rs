@@ -0,0 +1,174 @@
+//!
+//! Verify that half precision and fp8 tensors can be used
+//!
+
+use comfy_builder_core::half::bf16;
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<bf16>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    image: Image<bf16>,
+}
+
+#[node]
+struct InvertHalf;
+
+impl Node for InvertHalf {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            image: Image::from_tensor(input.image.affine(-1.0, 1.0)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nodes::fixtures;
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+    use comfy_builder_core::types::torch::{self, TorchDType};
+    use pyo3::prelude::*;
+    use pyo3::types::PyString;
+
+    /// Values each dtype holds exactly, with the bytes torch stores them as.
+    fn cases() -> Vec<(TorchDType, [f32; 4], Vec<u8>)> {
+        let half = |bits: [u16; 4]| bits.iter().flat_map(|bits| bits.to_ne_bytes()).collect();
+
+        vec![
+            (
+                TorchDType::Float16,
+                [1.0, 0.5, -2.0, 0.1],
+                half([0x3C00, 0x3800, 0xC000, 0x2E66]),
+            ),
+            (
+                TorchDType::BFloat16,
+                [1.0, 0.5, -2.0, 0.1],
+                half([0x3F80, 0x3F00, 0xC000, 0x3DCD]),
+            ),
+            (
+                TorchDType::Float8E4M3Fn,
+                [1.0, 0.5, -2.0, 448.0],
+                vec![0x38, 0x30, 0xC0, 0x7E],
+            ),
+            (
+                TorchDType::Float8E5M2,
+                [1.0, 0.5, -2.0, 57344.0],
+                vec![0x3C, 0x38, 0xC0, 0x7B],
+            ),
+        ]
+    }
+
+    fn bytes(tensor: &Bound<PyAny>) -> Vec<u8> {
+        tensor.getattr("_data").unwrap().extract().unwrap()
+    }
+
+    fn values(tensor: &Tensor) -> Vec<f32> {
+        tensor
+            .to_dtype(DType::F32)
+            .unwrap()
+            .flatten_all()
+            .unwrap()
+            .to_vec1()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_half_precision_image() -> comfy_builder_core::candle::Result<()> {
+        let image = Tensor::new(&[0.0f32, 0.25, 0.5, 1.0], &Device::Cpu)?
+            .reshape((1, 1, 1, 4))?
+            .to_dtype(DType::BF16)?;
+
+        let output = run_node!(
+            InvertHalf,
+            Input {
+                image: Image::from_tensor(image),
+            }
+        );
+
+        assert_eq!(output.image.dtype(), DType::BF16);
+        assert_eq!(
+            output.image.to_dtype(DType::F32)?.flatten_all()?.to_vec1::<f32>()?,
+            vec![1.0, 0.75, 0.5, 0.0]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_torch_dtypes() {
+        Python::initialize();
+        Python::attach(|python| {
+            let parse = |name: &str| TorchDType::from_torch(PyString::new(python, name).as_any());
+
+            assert_eq!(parse("torch.bfloat16").unwrap(), TorchDType::BFloat16);
+            assert_eq!(parse("torch.float8_e4m3fn").unwrap(), TorchDType::Float8E4M3Fn);
+            assert_eq!(parse("torch.float8_e5m2").unwrap(), TorchDType::Float8E5M2);
+            assert!(parse("torch.complex64").is_err());
+        });
+
+        assert_eq!(TorchDType::Float8E4M3Fn.candle_dtype(), DType::F16);
+        assert_eq!(TorchDType::BFloat16.candle_dtype(), DType::BF16);
+        assert_eq!(TorchDType::from(DType::F16), TorchDType::Float16);
+        assert_eq!(TorchDType::Float8E5M2.to_string(), "torch.float8_e5m2");
+    }
+
+    #[test]
+    pub fn test_torch_byte_round_trips() {
+        let _serial = fixtures::serial();
+
+        Python::initialize();
+        Python::attach(|python| {
+            let _torch = fixtures::torch(python);
+
+            for (dtype, input, expected) in cases() {
+                let tensor = Tensor::new(&input, &Device::Cpu).unwrap().reshape((2, 2)).unwrap();
+                let rounded = values(&tensor.to_dtype(dtype.candle_dtype()).unwrap());
+
+                let handed = torch::into_torch(python, &tensor, dtype).unwrap();
+
+                assert_eq!(handed.getattr("dtype").unwrap().to_string(), dtype.to_string());
+                assert_eq!(
+                    handed.getattr("shape").unwrap().extract::<Vec<usize>>().unwrap(),
+                    [2, 2]
+                );
+                assert_eq!(bytes(&handed), expected, "{} narrowed by into_torch", dtype);
+
+                // Torch can't export fp8 through DLPack, so those are read back by copying.
+                if !dtype.is_fp8() {
+                    let (read, read_dtype) = torch::from_torch(handed, &Device::Cpu).unwrap();
+
+                    assert_eq!((read.dtype(), read_dtype), (dtype.candle_dtype(), dtype));
+                    assert_eq!(values(&read), rounded);
+                }
+
+                if !fixtures::has_numpy(python) {
+                    eprintln!("numpy is not installed, skipping the copying conversions of {}", dtype);
+                    continue;
+                }
+
+                let copied = torch::copy_into_torch(python, &tensor, dtype).unwrap();
+
+                assert_eq!(copied.getattr("dtype").unwrap().to_string(), dtype.to_string());
+                assert_eq!(bytes(&copied), expected, "{} narrowed by copy_into_torch", dtype);
+
+                let (read, read_dtype) = torch::copy_from_torch(copied, &Device::Cpu).unwrap();
+
+                assert_eq!((read.dtype(), read_dtype), (dtype.candle_dtype(), dtype));
+                assert_eq!(read.dims(), &[2, 2]);
+                assert_eq!(values(&read), rounded, "{} widened by copy_from_torch", dtype);
+            }
+        });
+    }
+}
@@ -0,0 +1,113 @@
+//!
+//! Verify that opaque model handles are passed through untouched
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{
+    Clip, ClipVision, ControlNet, Gligen, Model, NodeInput, NodeOutput, StyleModel, UpscaleModel, Vae, node,
+};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    model: Model,
+    clip: Clip,
+    vae: Option<Vae>,
+    control_net_a: ControlNet,
+    control_net_b: Option<ControlNet>,
+    use_b: bool,
+    clip_vision: Option<ClipVision>,
+    upscale_model: Option<UpscaleModel>,
+    style_model: Option<StyleModel>,
+    gligen: Option<Gligen>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    model: Model,
+    clip: Clip,
+    vae: Option<Vae>,
+    control_net: ControlNet,
+    clip_vision: Option<ClipVision>,
+    upscale_model: Option<UpscaleModel>,
+    style_model: Option<StyleModel>,
+    gligen: Option<Gligen>,
+}
+
+#[node]
+struct Handles;
+
+impl Node for Handles {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let control_net = match (input.use_b, input.control_net_b) {
+            (true, Some(control_net)) => control_net,
+            _ => input.control_net_a,
+        };
+
+        Ok(Output {
+            model: input.model,
+            clip: input.clip,
+            vae: input.vae,
+            control_net,
+            clip_vision: input.clip_vision,
+            upscale_model: input.upscale_model,
+            style_model: input.style_model,
+            gligen: input.gligen,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::{AsInput, ComfyType};
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyString;
+
+    fn object(python: Python, name: &str) -> Py<PyAny> {
+        PyString::new(python, name).into_any().unbind()
+    }
+
+    #[test]
+    pub fn test_handles() {
+        Python::initialize();
+        Python::attach(|python| {
+            let output = run_node!(
+                Handles,
+                Input {
+                    model: Model::new(object(python, "model")),
+                    clip: Clip::new(object(python, "clip")),
+                    vae: None,
+                    control_net_a: ControlNet::new(object(python, "control_net_a")),
+                    control_net_b: Some(ControlNet::new(object(python, "control_net_b"))),
+                    use_b: true,
+                    clip_vision: None,
+                    upscale_model: Some(UpscaleModel::new(object(python, "upscale_model"))),
+                    style_model: None,
+                    gligen: None,
+                }
+            );
+
+            assert_eq!(output.model.bind(python).to_string(), "model");
+            assert_eq!(output.clip.bind(python).to_string(), "clip");
+            assert!(output.vae.is_none());
+            assert_eq!(output.control_net.bind(python).to_string(), "control_net_b");
+            assert_eq!(output.upscale_model.unwrap().bind(python).to_string(), "upscale_model");
+            assert!(output.style_model.is_none());
+        });
+    }
+
+    #[test]
+    pub fn test_handle_types() {
+        assert_eq!(ControlNet::comfy_type(), ComfyType::ControlNet);
+        assert_eq!(ClipVision::comfy_type(), ComfyType::ClipVision);
+        assert_eq!(UpscaleModel::comfy_type(), ComfyType::UpscaleModel);
+        assert_eq!(StyleModel::comfy_type(), ComfyType::StyleModel);
+        assert_eq!(Gligen::comfy_type(), ComfyType::Gligen);
+        assert_eq!(ComfyType::ClipVision.to_string(), "ClipVision");
+    }
+}
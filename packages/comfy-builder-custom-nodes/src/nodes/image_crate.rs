@@ -0,0 +1,89 @@
+//!
+//! Verify that images can be processed with the `image` crate
+//!
+
+use comfy_builder_core::image::DynamicImage;
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    image: Image<f32>,
+}
+
+#[node]
+struct FlipWithImageCrate;
+
+impl Node for FlipWithImageCrate {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let frames: Vec<DynamicImage> = Vec::try_from(&input.image)?;
+        let frames: Vec<DynamicImage> = frames.iter().map(|frame| frame.fliph()).collect();
+
+        Ok(Output {
+            image: Image::try_from(&frames[..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::image::{ImageBuffer, Luma, Rgb, Rgba, RgbaImage};
+    use comfy_builder_core::run_node;
+
+    #[test]
+    pub fn test_image_crate_round_trip() -> comfy_builder_core::candle::Result<()> {
+        let pixels = ImageBuffer::from_fn(2, 1, |x, _| Rgb([x as u8 * 255, 0u8, 51u8]));
+        let image = Image::<f32>::try_from(DynamicImage::ImageRgb8(pixels))?;
+
+        assert_eq!(
+            (image.batch_size()?, image.height()?, image.width()?, image.channels()?),
+            (1, 1, 2, 3)
+        );
+        assert_eq!(
+            image.flatten_all()?.to_vec1::<f32>()?,
+            vec![0.0, 0.0, 0.2, 1.0, 0.0, 0.2]
+        );
+
+        let output = run_node!(FlipWithImageCrate, Input { image });
+        let flipped = DynamicImage::try_from(&output.image)?.to_rgb8();
+
+        assert_eq!(flipped.get_pixel(0, 0), &Rgb([255, 0, 51]));
+        assert_eq!(flipped.get_pixel(1, 0), &Rgb([0, 0, 51]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_image_crate_bit_depth_and_alpha() -> comfy_builder_core::candle::Result<()> {
+        let deep = ImageBuffer::from_pixel(1, 1, Rgb([u16::MAX, 0, u16::MAX / 2]));
+        let image = Image::<f32>::try_from(DynamicImage::ImageRgb16(deep))?;
+
+        assert_eq!(image.max_all()?.to_scalar::<f32>()?, 1.0);
+
+        let mut transparent = RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255]));
+
+        transparent.put_pixel(1, 1, Rgba([10, 20, 30, 0]));
+
+        let (image, mask) = Image::<f32>::from_dynamic_with_mask(&[DynamicImage::ImageRgba8(transparent)])?;
+
+        assert_eq!(image.channels()?, 3);
+        assert_eq!(mask.flatten_all()?.to_vec1::<f32>()?, vec![0.0, 0.0, 0.0, 1.0]);
+
+        let masks: Vec<ImageBuffer<Luma<f32>, Vec<f32>>> = Vec::try_from(&mask)?;
+
+        assert_eq!(masks[0].get_pixel(1, 1), &Luma([1.0]));
+
+        Ok(())
+    }
+}
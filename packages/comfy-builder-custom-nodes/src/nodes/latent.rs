@@ -0,0 +1,87 @@
+//!
+//! Verify that latent metadata is kept and can be edited from Rust
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Latent, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    latent: Latent,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    latent: Latent,
+}
+
+#[node]
+struct LatentBatchIndex;
+
+impl Node for LatentBatchIndex {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let mut latent = input.latent;
+
+        if latent.batch_index().is_none() {
+            let batch_size = latent.samples().dims()[0];
+
+            latent.set_batch_index(Some((0..batch_size).collect()));
+        }
+
+        Ok(Output { latent })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::Device;
+    use comfy_builder_core::prelude::Image;
+    use comfy_builder_core::run_node;
+    use pyo3::types::{PyDict, PyInt};
+
+    #[test]
+    pub fn test_latent_metadata() -> comfy_builder_core::candle::Result<()> {
+        Python::initialize();
+        Python::attach(|python| {
+            let mut latent = Latent::from(Image::from_raw(vec![0.0; 2 * 4 * 8 * 8], (2, 4, 8, 8), &Device::Cpu)?);
+
+            latent.set_latent_type(Some("audio".to_string()));
+            latent.set_extra("downscale_ratio_spacial", PyInt::new(python, 8).into_any().unbind());
+
+            let output = run_node!(LatentBatchIndex, Input { latent });
+
+            assert_eq!(output.latent.batch_index(), Some(&[0, 1][..]));
+            assert_eq!(output.latent.latent_type(), Some("audio"));
+            assert_eq!(
+                output
+                    .latent
+                    .get_extra(python, "downscale_ratio_spacial")
+                    .map(|value| value.extract::<u32>().unwrap()),
+                Some(8)
+            );
+
+            let copy = output.latent.clone();
+
+            assert_eq!(copy.batch_index(), Some(&[0, 1][..]));
+            assert!(copy.get_extra(python, "downscale_ratio_spacial").is_some());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    pub fn test_latent_without_samples() {
+        Python::initialize();
+        Python::attach(|python| {
+            let error = Latent::<f32>::new(PyDict::new(python).into_any()).unwrap_err();
+
+            assert!(error.to_string().contains("missing `samples`"));
+        });
+    }
+}
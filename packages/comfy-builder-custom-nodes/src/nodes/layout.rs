@@ -0,0 +1,113 @@
+//!
+//! Verify that images and masks know their layout and reject wrongly shaped tensors
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, Mask, NodeInput, NodeOutput, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    mirrored: Image<f32>,
+    mask: Mask<f32>,
+}
+
+#[node]
+struct MirrorFrames;
+
+impl Node for MirrorFrames {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let frames = input
+            .image
+            .frames()?
+            .map(|frame| Image::from_bchw(frame?.to_bchw()?.flip(&[3])?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mirrored = Image::stack(&frames)?;
+        let mask = Mask::try_from_image(Image::from_tensor(mirrored.narrow(3, 0, 1)?))?;
+
+        Ok(Output { mirrored, mask })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+
+    #[test]
+    pub fn test_image_layout() -> comfy_builder_core::candle::Result<()> {
+        let image = Tensor::arange(0f32, 24f32, &Device::Cpu)?.reshape((2, 2, 2, 3))?;
+        let output = run_node!(
+            MirrorFrames,
+            Input {
+                image: Image::from_tensor(image),
+            }
+        );
+
+        let mirrored = &output.mirrored;
+
+        assert_eq!(
+            (
+                mirrored.batch_size()?,
+                mirrored.height()?,
+                mirrored.width()?,
+                mirrored.channels()?
+            ),
+            (2, 2, 2, 3)
+        );
+        assert_eq!(mirrored.frames()?.count(), 2);
+        assert_eq!(
+            mirrored.narrow(3, 0, 1)?.flatten_all()?.to_vec1::<f32>()?,
+            vec![3.0, 0.0, 9.0, 6.0, 15.0, 12.0, 21.0, 18.0]
+        );
+        assert_eq!(
+            (output.mask.batch_size()?, output.mask.height()?, output.mask.width()?),
+            (2, 2, 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_rejects_invalid_shapes() -> comfy_builder_core::candle::Result<()> {
+        let flat = Tensor::zeros((2, 8, 8), DType::F32, &Device::Cpu)?;
+        let channels_first = Tensor::zeros((1, 3, 8, 8), DType::F32, &Device::Cpu)?;
+
+        assert!(Image::<f32>::try_from_tensor(flat.clone()).is_err());
+        assert!(Image::<f32>::try_from_tensor(channels_first.clone()).is_err());
+        assert_eq!(Image::<f32>::from_bchw(channels_first.clone())?.channels()?, 3);
+
+        assert!(Mask::<f32>::try_from_tensor(flat.clone()).is_ok());
+        assert!(Mask::<f32>::try_from_tensor(Tensor::zeros((1, 8, 8, 1), DType::F32, &Device::Cpu)?).is_err());
+        assert!(Mask::try_from_image(Image::<f32>::from_raw(vec![0.0; 192], (1, 8, 8, 3), &Device::Cpu)?).is_err());
+
+        // The unchecked constructors and conversions still wrap any tensor as is.
+        assert_eq!(Image::<f32>::from_tensor(channels_first).dims(), &[1, 3, 8, 8]);
+        assert_eq!(Mask::from(Image::<f32>::from_tensor(flat.clone())).dims(), &[2, 8, 8]);
+
+        // Their layout accessors fail instead of reading the wrong dimension, e.g. for waveforms.
+        let waveform = Image::<f32>::from_tensor(flat);
+
+        assert!(waveform.channels().is_err());
+        assert!(waveform.height().is_err());
+        assert!(waveform.frames().is_err());
+        assert!(
+            Mask::<f32>::from_tensor(Tensor::zeros((1, 8, 8, 1), DType::F32, &Device::Cpu)?)
+                .width()
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//!
+//! Verify that template sockets forward whichever value matches the connected type
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeInput, NodeOutput, node};
+use comfy_builder_core::types::match_type::Match;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    on_true: Match<'T'>,
+    on_false: Match<'T'>,
+    condition: bool,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    output: Match<'T'>,
+}
+
+#[node]
+struct MatchSwitch;
+
+impl Node for MatchSwitch {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            output: match input.condition {
+                true => input.on_true,
+                false => input.on_false,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyString;
+
+    #[test]
+    pub fn test_match_switch() {
+        Python::initialize();
+        Python::attach(|python| {
+            let output = run_node!(
+                MatchSwitch,
+                Input {
+                    on_true: Match::new(PyString::new(python, "yes").into_any().unbind()),
+                    on_false: Match::new(PyString::new(python, "no").into_any().unbind()),
+                    condition: false,
+                }
+            );
+
+            assert_eq!(output.output.extract::<String>(python).unwrap(), "no");
+        });
+    }
+}
@@ -1,8 +1,34 @@
+mod any;
+mod audio;
+mod autogrow;
+mod conditioning;
 mod custom;
+mod custom_type;
+mod dlpack;
+mod dtype_policy;
+mod dynamic_options;
 mod r#enum;
+#[cfg(test)]
+mod fixtures;
+mod geometry;
+mod guider;
+mod half;
+mod handles;
+mod image_crate;
+mod latent;
+mod layout;
+mod match_type;
+mod multi_select;
 mod options;
+mod placement;
 mod primitives;
+mod ranged;
+mod sampler;
+mod scheduler;
+mod shared;
+mod tensor_cache;
 mod tensors;
 mod unit;
 mod vector;
+mod video;
 mod attributes;
@@ -0,0 +1,86 @@
+//!
+//! Verify that multi-select combos decode into a list of enum variants without enabling list mode
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Enum, NodeInput, NodeOutput, node};
+use comfy_builder_core::types::multi_select::MultiSelect;
+use std::error::Error;
+
+#[derive(Enum, Debug, Clone, PartialEq)]
+enum Channel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    channels: MultiSelect<Channel>,
+    extra: Option<MultiSelect<Channel>>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    channels: MultiSelect<Channel>,
+    count: usize,
+}
+
+#[node]
+struct ChannelSelect;
+
+impl Node for ChannelSelect {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let mut channels = input.channels.into_inner();
+
+        channels.extend(input.extra.map(MultiSelect::into_inner).unwrap_or_default());
+
+        Ok(Output {
+            count: channels.len(),
+            channels: MultiSelect::new(channels),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::In;
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyList;
+
+    #[test]
+    pub fn test_multi_select() {
+        let output = run_node!(
+            ChannelSelect,
+            Input {
+                channels: MultiSelect::new(vec![Channel::Red, Channel::Blue]),
+                extra: Some(vec![Channel::Alpha].into()),
+            }
+        );
+
+        assert!(!Input::is_list());
+        assert_eq!(output.count, 3);
+        assert_eq!(*output.channels, vec![Channel::Red, Channel::Blue, Channel::Alpha]);
+    }
+
+    #[test]
+    pub fn test_multi_select_decoding() {
+        Python::initialize();
+        Python::attach(|python| {
+            let list = PyList::new(python, ["Green", "Alpha"]).unwrap();
+            let channels = list.extract::<MultiSelect<Channel>>().unwrap();
+
+            assert_eq!(*channels, vec![Channel::Green, Channel::Alpha]);
+
+            let list = PyList::new(python, ["Purple"]).unwrap();
+
+            assert!(list.extract::<MultiSelect<Channel>>().is_err());
+        });
+    }
+}
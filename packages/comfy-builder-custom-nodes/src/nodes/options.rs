@@ -51,4 +51,35 @@ mod test {
         assert_eq!(output.string, String::default());
         assert_eq!(output.string_option, None);
     }
+
+    #[test]
+    pub fn test_invalid_values() {
+        use comfy_builder_core::prelude::Kwargs;
+        use pyo3::prelude::*;
+        use pyo3::types::PyDict;
+
+        Python::initialize();
+        Python::attach(|python| {
+            let kwargs = PyDict::new(python);
+
+            kwargs.set_item("string", "value").unwrap();
+            kwargs.set_item("string_option", 42).unwrap();
+
+            let input = Input::try_from(Kwargs(Some(kwargs.clone()))).unwrap();
+
+            assert_eq!(input.string_option, None, "invalid optional values fall back to `None`");
+
+            kwargs.set_item("string", 42).unwrap();
+
+            let error = Input::try_from(Kwargs(Some(kwargs.clone()))).err().unwrap().to_string();
+
+            assert!(error.contains("invalid value for input `string`"), "{}", error);
+
+            kwargs.del_item("string").unwrap();
+
+            let error = Input::try_from(Kwargs(Some(kwargs))).err().unwrap().to_string();
+
+            assert!(error.contains("unable to retrieve attribute"), "{}", error);
+        });
+    }
 }
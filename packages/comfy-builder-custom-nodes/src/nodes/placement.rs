@@ -0,0 +1,82 @@
+//!
+//! Verify that outputs can be placed on the device and with the dtype of their inputs
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, OutputPlacement, node};
+use comfy_builder_core::types::sigmas::Sigmas;
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    image: Image<f32>,
+    sigmas: Sigmas,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    image: Image<f32>,
+    high: Sigmas,
+    low: Sigmas,
+}
+
+#[node]
+struct BrightenInPlace;
+
+impl Node for BrightenInPlace {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    const OUTPUT_PLACEMENT: OutputPlacement = OutputPlacement::Source;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let placement = input.image.placement().cloned();
+        let image = Image::from_tensor(input.image.affine(1.0, 0.1)?.clamp(0.0, 1.0)?).with_placement(placement);
+        let (high, low) = input.sigmas.split(1)?;
+
+        Ok(Output { image, high, low })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::Device;
+    use comfy_builder_core::run_node;
+    use comfy_builder_core::types::torch::{Placement, TorchDType};
+
+    #[test]
+    pub fn test_output_placement() -> comfy_builder_core::candle::Result<()> {
+        let half = Placement::new("cpu", TorchDType::Float16);
+        let image = Image::from_raw(vec![0.5f32; 12], (1, 2, 2, 3), &Device::Cpu)?.with_placement(Some(half.clone()));
+        let sigmas = Sigmas::from_values(&[14.6, 1.0, 0.0])?.with_placement(Some(half.clone()));
+
+        let output = run_node!(BrightenInPlace, Input { image, sigmas });
+
+        assert_eq!(output.image.placement(), Some(&half));
+        assert_eq!(output.high.placement(), Some(&half));
+        assert_eq!(output.low.placement(), Some(&half));
+
+        let declared = TorchDType::Float32;
+
+        assert_eq!(
+            BrightenInPlace::OUTPUT_PLACEMENT.resolve(declared, output.image.placement()),
+            half
+        );
+        assert_eq!(
+            OutputPlacement::Declared.resolve(declared, output.image.placement()),
+            Placement::new("cpu", TorchDType::Float32)
+        );
+        assert_eq!(
+            OutputPlacement::Source.resolve(declared, None),
+            Placement::new("cpu", TorchDType::Float32)
+        );
+
+        OutputPlacement::Source.scope(|| assert_eq!(OutputPlacement::current(), OutputPlacement::Source));
+
+        assert_eq!(OutputPlacement::current(), OutputPlacement::Declared);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//!
+//! Verify that bounded numeric inputs reject values outside of their range
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeInput, NodeOutput, node};
+use comfy_builder_core::types::ranged::{RangedF32, RangedU32};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    steps: RangedU32<1, 100>,
+    denoise: RangedF32<0, 1>,
+    shift: Option<RangedF32<-25, 25, 10>>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    steps: RangedU32<1, 100>,
+    denoise: f32,
+}
+
+#[node]
+struct Ranged;
+
+impl Node for Ranged {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let shift = input.shift.map(|shift| *shift).unwrap_or_default();
+
+        Ok(Output {
+            steps: input.steps,
+            denoise: (*input.denoise + shift).clamp(0.0, 1.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::prelude::Kwargs;
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyDict;
+
+    #[test]
+    pub fn test_ranged() {
+        let output = run_node!(
+            Ranged,
+            Input {
+                steps: RangedU32::new(20).unwrap(),
+                denoise: RangedF32::new(0.5).unwrap(),
+                shift: Some(RangedF32::new(0.25).unwrap()),
+            }
+        );
+
+        assert_eq!(*output.steps, 20);
+        assert_eq!(output.denoise, 0.75);
+
+        assert_eq!(RangedF32::<-25, 25, 10>::min(), -2.5);
+        assert_eq!(RangedF32::<-25, 25, 10>::max(), 2.5);
+        assert!(RangedF32::<-25, 25, 10>::new(2.6).is_err());
+        assert!(RangedU32::<1, 100>::new(0).is_err());
+        assert!(RangedF32::<-25, 25, 10>::new(f32::NAN).is_err());
+        assert!(comfy_builder_core::types::ranged::RangedF64::<0, 1>::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    pub fn test_ranged_rejects_out_of_range_input() {
+        Python::initialize();
+        Python::attach(|python| {
+            let kwargs = PyDict::new(python);
+
+            kwargs.set_item("steps", 150).unwrap();
+            kwargs.set_item("denoise", 0.5).unwrap();
+
+            let error = Input::try_from(Kwargs(Some(kwargs))).err().unwrap().to_string();
+
+            assert!(error.contains("`steps`"));
+            assert!(error.contains("150 is outside the allowed range [1, 100]"));
+        });
+    }
+}
@@ -0,0 +1,100 @@
+//!
+//! Verify that a sampling loop written in Rust can be handed to ComfyUI as a `SAMPLER`
+//!
+
+use comfy_builder_core::candle::Tensor;
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeOutput, Sampler, node};
+use comfy_builder_core::types::sampler::{Denoiser, SamplerFunction, SamplingError};
+use std::error::Error;
+
+pub struct Euler;
+
+impl SamplerFunction for Euler {
+    fn sample(&self, model: &dyn Denoiser, latent: Tensor, sigmas: &Tensor) -> Result<Tensor, SamplingError> {
+        let sigmas = sigmas.flatten_all()?.to_vec1::<f32>()?;
+        let mut x = latent;
+
+        for (step, window) in sigmas.windows(2).enumerate() {
+            let (sigma, sigma_next) = (window[0] as f64, window[1] as f64);
+            let denoised = model.denoise(&x, sigma)?;
+
+            model.report(step, &x, sigma, &denoised)?;
+
+            let derivative = ((&x - &denoised)? / sigma)?;
+
+            x = (x + (derivative * (sigma_next - sigma))?)?;
+        }
+
+        Ok(x)
+    }
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    sampler: Sampler,
+}
+
+#[node]
+struct RustEulerSampler;
+
+impl Node for RustEulerSampler {
+    type In = ();
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, _: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            sampler: Sampler::new(Euler),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{DType, Device};
+    use comfy_builder_core::run_node;
+    use std::cell::RefCell;
+
+    /// Predicts a blank image regardless of the input, so every step scales `x` by `sigma_next / sigma`.
+    struct Blank {
+        steps: RefCell<Vec<usize>>,
+    }
+
+    impl Denoiser for Blank {
+        fn denoise(&self, x: &Tensor, _: f64) -> Result<Tensor, SamplingError> {
+            Ok(x.zeros_like()?)
+        }
+
+        fn report(&self, step: usize, _: &Tensor, _: f64, _: &Tensor) -> Result<(), SamplingError> {
+            self.steps.borrow_mut().push(step);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn test_euler_sampler() -> Result<(), SamplingError> {
+        let model = Blank {
+            steps: RefCell::new(vec![]),
+        };
+
+        let latent = Tensor::ones((1, 4, 8, 8), DType::F32, &Device::Cpu)?;
+        let sigmas = Tensor::new(&[2.0f32, 1.0, 0.5], &Device::Cpu)?;
+
+        let output = Euler.sample(&model, latent, &sigmas)?;
+        let values = output.flatten_all()?.to_vec1::<f32>()?;
+
+        assert_eq!(output.dims(), &[1, 4, 8, 8]);
+        assert!(values.iter().all(|value| (value - 0.25).abs() < 1e-6));
+        assert_eq!(*model.steps.borrow(), vec![0, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_sampler_node() {
+        assert!(run_node!(RustEulerSampler, (), return).is_ok());
+    }
+}
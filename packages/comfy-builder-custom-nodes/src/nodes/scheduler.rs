@@ -0,0 +1,260 @@
+//!
+//! Verify that every scheduler produces the same sigmas as ComfyUI
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Enum, NodeInput, NodeOutput, node};
+use comfy_builder_core::scheduler::{self, AysModel, DiscreteSchedule};
+use comfy_builder_core::types::sigmas::Sigmas;
+use std::error::Error;
+
+#[derive(Enum, Debug, PartialEq)]
+enum Schedule {
+    Karras,
+    Exponential,
+    Polyexponential,
+    Simple,
+    Normal,
+    SgmUniform,
+    Beta,
+    AlignYourSteps,
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    schedule: Schedule,
+    #[min = 1]
+    #[max = 10000]
+    steps: usize,
+    #[min = 0.0]
+    sigma_min: f64,
+    #[min = 0.0]
+    sigma_max: f64,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    sigmas: Sigmas,
+}
+
+#[node]
+struct RustScheduler;
+
+impl Node for RustScheduler {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        let model = DiscreteSchedule::default();
+        let (steps, sigma_min, sigma_max) = (input.steps, input.sigma_min, input.sigma_max);
+
+        let sigmas = match input.schedule {
+            Schedule::Karras => scheduler::karras(steps, sigma_min, sigma_max, 7.0)?,
+            Schedule::Exponential => scheduler::exponential(steps, sigma_min, sigma_max)?,
+            Schedule::Polyexponential => scheduler::polyexponential(steps, sigma_min, sigma_max, 2.0)?,
+            Schedule::Simple => scheduler::simple(&model, steps)?,
+            Schedule::Normal => scheduler::normal(&model, steps)?,
+            Schedule::SgmUniform => scheduler::sgm_uniform(&model, steps)?,
+            Schedule::Beta => scheduler::beta(&model, steps, 0.6, 0.6)?,
+            Schedule::AlignYourSteps => scheduler::align_your_steps(AysModel::Sd1, steps)?,
+        };
+
+        Ok(Output { sigmas })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::run_node;
+
+    const SIGMA_MIN: f64 = 0.0291675;
+    const SIGMA_MAX: f64 = 14.614642;
+
+    fn schedule(schedule: Schedule, steps: usize) -> Vec<f64> {
+        let output = run_node!(
+            RustScheduler,
+            Input {
+                schedule,
+                steps,
+                sigma_min: SIGMA_MIN,
+                sigma_max: SIGMA_MAX,
+            }
+        );
+
+        output.sigmas.to_vec().unwrap()
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() <= 1e-5 * expected.abs().max(1.0),
+                "{} != {}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_sigma_schedules() {
+        assert_close(
+            &schedule(Schedule::Karras, 4),
+            &[14.614642, 3.1686095477659966, 0.446920586813722, 0.0291675, 0.0],
+        );
+        assert_close(
+            &schedule(Schedule::Exponential, 4),
+            &[14.614642, 1.8400312113472204, 0.23166594561344153, 0.0291675, 0.0],
+        );
+        assert_close(
+            &schedule(Schedule::Polyexponential, 4),
+            &[14.614642, 0.4622212013677241, 0.058195160515257256, 0.0291675, 0.0],
+        );
+    }
+
+    #[test]
+    pub fn test_model_schedules() {
+        let model = DiscreteSchedule::default();
+
+        assert_close(
+            &[model.sigma_min().unwrap(), model.sigma_max().unwrap()],
+            &[0.029167158151720367, 14.614641229333646],
+        );
+        assert_close(
+            &schedule(Schedule::Simple, 4),
+            &[
+                14.614641229333646,
+                4.081729363724309,
+                1.612886194303876,
+                0.6932048996386885,
+                0.0,
+            ],
+        );
+        assert_close(
+            &schedule(Schedule::Normal, 4),
+            &[
+                14.614641229333644,
+                2.9183071154268285,
+                0.9323579668348474,
+                0.029167158151720367,
+                0.0,
+            ],
+        );
+        assert_close(
+            &schedule(Schedule::SgmUniform, 4),
+            &[
+                14.614641229333644,
+                4.086081071209323,
+                1.6155802601603273,
+                0.695149519078429,
+                0.0,
+            ],
+        );
+
+        let empty = DiscreteSchedule::from_sigmas(vec![]);
+
+        assert_eq!(empty.sigma_min(), None);
+        assert_eq!(empty.sigma_max(), None);
+        assert_eq!(empty.sigma(0.0), None);
+        assert!(scheduler::simple::<f32>(&empty, 4).is_err());
+        assert!(scheduler::normal::<f32>(&empty, 4).is_err());
+        assert!(scheduler::sgm_uniform::<f32>(&empty, 4).is_err());
+        assert!(scheduler::beta::<f32>(&empty, 4, 0.6, 0.6).is_err());
+    }
+
+    #[test]
+    pub fn test_beta_schedule() -> comfy_builder_core::candle::Result<()> {
+        let model = DiscreteSchedule::default();
+
+        // With alpha = beta = 1 the distribution is uniform, and with alpha = 2, beta = 1 its
+        // quantile function is `sqrt(q)`, which gives exact timesteps to compare against.
+        assert_close(
+            &scheduler::beta::<f32>(&model, 4, 1.0, 1.0)?.to_vec()?,
+            &[
+                14.614641229333646,
+                4.081729363724309,
+                1.6182788260186167,
+                0.6957989370616523,
+                0.0,
+            ],
+        );
+        assert_close(
+            &scheduler::beta::<f32>(&model, 4, 2.0, 1.0)?.to_vec()?,
+            &[
+                14.614641229333646,
+                6.973851179920288,
+                3.4157017927474462,
+                1.6182788260186167,
+                0.0,
+            ],
+        );
+
+        // ComfyUI's defaults, with the timesteps from `beta_scheduler` in `comfy.samplers`, which
+        // uses `scipy.stats.beta.ppf`.
+        assert_close(
+            &scheduler::beta::<f32>(&model, 10, 0.6, 0.6)?.to_vec()?,
+            &[
+                14.614641229333646,
+                11.542770829724676,
+                7.371844096529516,
+                4.3728017340602365,
+                2.6152358253967747,
+                1.6182788260186167,
+                1.0273293400782555,
+                0.6548853595568945,
+                0.39550969342690917,
+                0.1990586286735258,
+                0.0,
+            ],
+        );
+
+        let sigmas = schedule(Schedule::Beta, 20);
+
+        assert_close(&sigmas[..1], &[model.sigma_max().unwrap()]);
+        assert!(sigmas.windows(2).all(|window| window[0] > window[1]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_align_your_steps() {
+        assert_close(
+            &schedule(Schedule::AlignYourSteps, 5),
+            &[
+                14.6146412293,
+                3.8636745985,
+                1.8841921177,
+                0.9642583904,
+                0.3977456272,
+                0.0,
+            ],
+        );
+        assert_eq!(schedule(Schedule::AlignYourSteps, 10).len(), 11);
+        assert_eq!(schedule(Schedule::AlignYourSteps, 10)[1] as f32, 6.474_576);
+    }
+
+    #[test]
+    pub fn test_sigma_operations() -> comfy_builder_core::candle::Result<()> {
+        let sigmas = Sigmas::<f32>::from_values(&[4.0, 3.0, 2.0, 1.0, 0.0])?;
+
+        let (high, low) = sigmas.split(2)?;
+
+        assert_eq!(high.to_vec()?, vec![4.0, 3.0, 2.0]);
+        assert_eq!(low.to_vec()?, vec![2.0, 1.0, 0.0]);
+        assert_eq!(high.concat(&low)?.to_vec()?, sigmas.to_vec()?);
+
+        let (all, none) = sigmas.split(10)?;
+
+        assert_eq!(all.len(), 5);
+        assert_eq!(none.to_vec()?, vec![0.0]);
+
+        let flipped = sigmas.flip()?.to_vec()?;
+
+        assert_eq!(flipped, vec![0.0001f32 as f64, 1.0, 2.0, 3.0, 4.0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+//!
+//! Verify that Rust values are handed between nodes without being copied or converted
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeInput, NodeOutput, node};
+use comfy_builder_core::types::shared::Shared;
+use std::error::Error;
+
+#[derive(Debug, PartialEq)]
+pub struct Tracks {
+    points: Vec<(f32, f32)>,
+}
+
+#[derive(NodeInput)]
+pub struct Input {
+    tracks: Shared<Tracks>,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    tracks: Shared<Tracks>,
+    count: usize,
+}
+
+#[node]
+struct SharedPassthrough;
+
+impl Node for SharedPassthrough {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, input: Self::In) -> Result<Self::Out, Self::Error> {
+        Ok(Output {
+            count: input.tracks.points.len(),
+            tracks: input.tracks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::run_node;
+    use pyo3::types::PyString;
+    use std::sync::Arc;
+
+    #[test]
+    pub fn test_shared_passthrough() {
+        Python::initialize();
+        Python::attach(|python| {
+            let tracks = Shared::new(Tracks {
+                points: vec![(0.0, 0.0), (1.0, 1.0)],
+            });
+
+            let object = tracks.clone().into_pyobject(python).unwrap();
+            let output = run_node!(
+                SharedPassthrough,
+                Input {
+                    tracks: object.extract().unwrap(),
+                }
+            );
+
+            assert_eq!(output.count, 2);
+            assert!(Arc::ptr_eq(&output.tracks.into_arc(), &tracks.into_arc()));
+        });
+    }
+
+    #[test]
+    pub fn test_shared_type_mismatch() {
+        Python::initialize();
+        Python::attach(|python| {
+            let object = Shared::new(42u32).into_pyobject(python).unwrap();
+            let error = object.extract::<Shared<Tracks>>().unwrap_err().to_string();
+
+            assert!(error.contains("Tracks"));
+            assert!(error.contains("u32"));
+
+            let error = PyString::new(python, "tracks")
+                .extract::<Shared<Tracks>>()
+                .unwrap_err()
+                .to_string();
+
+            assert!(error.contains("received a Python object"));
+        });
+    }
+}
@@ -0,0 +1,168 @@
+//!
+//! Verify that converted tensors are reused while the tensor cache is enabled
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{NodeOutput, node};
+use comfy_builder_core::types::tensor_cache;
+use std::error::Error;
+
+#[derive(NodeOutput)]
+pub struct Output {
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+#[node(category = "profiling")]
+struct TensorCacheStats;
+
+impl Node for TensorCacheStats {
+    type In = ();
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    const IS_OUTPUT_NODE: bool = true;
+
+    fn execute(&self, _: Self::In) -> Result<Self::Out, Self::Error> {
+        let stats = tensor_cache::stats();
+
+        Ok(Output {
+            hits: stats.hits,
+            misses: stats.misses,
+            evictions: stats.evictions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nodes::fixtures::{self, ModuleGuard};
+    use comfy_builder_core::candle::{DType, Device, Tensor};
+    use comfy_builder_core::run_node;
+    use pyo3::prelude::*;
+    use pyo3::types::{PyDict, PyModule};
+    use std::cell::Cell;
+
+    /// Disables and empties the cache again once the test is over, even if it fails.
+    struct ResetCache;
+
+    impl Drop for ResetCache {
+        fn drop(&mut self) {
+            tensor_cache::configure(0);
+            tensor_cache::clear();
+            tensor_cache::reset_stats();
+        }
+    }
+
+    #[test]
+    pub fn test_tensor_cache() {
+        let _serial = fixtures::serial();
+        let _reset = ResetCache;
+
+        Python::initialize();
+        Python::attach(|python| {
+            let torch = fixtures::torch(python);
+            let tensor = |data_ptr: usize| {
+                let tensor = fixtures::tensor(&torch, "float32", &[0.0; 256], &[16, 16]);
+
+                tensor.setattr("_data_ptr", data_ptr).unwrap();
+                tensor
+            };
+
+            let conversions = Cell::new(0);
+            let convert = |object: &Bound<PyAny>| {
+                tensor_cache::get_or_convert(object, DType::F32, &Device::Cpu, || {
+                    conversions.set(conversions.get() + 1);
+
+                    Ok(Tensor::zeros((16, 16), DType::F32, &Device::Cpu).unwrap())
+                })
+                .unwrap()
+            };
+
+            let first = tensor(1);
+            let second = tensor(2);
+
+            convert(&first);
+            assert_eq!(conversions.get(), 1, "the cache is disabled by default");
+
+            // Room for a single 16x16 f32 tensor.
+            tensor_cache::configure(1024);
+            tensor_cache::reset_stats();
+
+            convert(&first);
+            convert(&first);
+            assert_eq!(conversions.get(), 2);
+
+            first.setattr("_version", 1).unwrap();
+            convert(&first);
+            assert_eq!(conversions.get(), 3, "in-place changes bump the version");
+
+            convert(&second);
+            convert(&first);
+            assert_eq!(conversions.get(), 5, "the least recently used tensor is evicted");
+
+            let output = run_node!(TensorCacheStats, ());
+
+            assert_eq!((output.hits, output.misses, output.evictions), (1, 4, 3));
+            assert_eq!(tensor_cache::stats().entries, 1);
+            assert_eq!(tensor_cache::stats().bytes, 1024);
+
+            tensor_cache::clear();
+            assert_eq!(tensor_cache::stats().entries, 0);
+
+            // Changing a returned tensor in place leaves the cached one untouched.
+            let ones = Tensor::ones((16, 16), DType::F32, &Device::Cpu).unwrap();
+
+            convert(&first).slice_set(&ones, 0, 0).unwrap();
+            convert(&first).slice_set(&ones, 0, 0).unwrap();
+            assert_eq!(conversions.get(), 6);
+            assert_eq!(convert(&first).sum_all().unwrap().to_scalar::<f32>().unwrap(), 0.0);
+
+            // Another tensor taking over the memory of one that is gone is not mistaken for it.
+            drop(first);
+            convert(&tensor(1));
+            assert_eq!(conversions.get(), 7);
+
+            // Entries are dropped once ComfyUI moves on to another prompt.
+            let server = ModuleGuard::install("server", PyModule::new(python, "server").unwrap());
+            let locals = PyDict::new(python);
+
+            python
+                .run(
+                    c"
+class PromptServer:
+    instance = None
+
+class Instance:
+    last_prompt_id = 'first'
+
+PromptServer.instance = Instance()
+",
+                    None,
+                    Some(&locals),
+                )
+                .unwrap();
+
+            server
+                .setattr("PromptServer", locals.get_item("PromptServer").unwrap())
+                .unwrap();
+
+            tensor_cache::enter_execution(python);
+            convert(&second);
+            tensor_cache::enter_execution(python);
+            assert_eq!(tensor_cache::stats().entries, 1);
+
+            locals
+                .get_item("Instance")
+                .unwrap()
+                .unwrap()
+                .setattr("last_prompt_id", "second")
+                .unwrap();
+
+            tensor_cache::enter_execution(python);
+            assert_eq!(tensor_cache::stats().entries, 0);
+        });
+    }
+}
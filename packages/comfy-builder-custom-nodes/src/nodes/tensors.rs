@@ -36,6 +36,7 @@ mod test {
     use comfy_builder_core::run_node;
 
     #[test]
+    #[allow(unused_variables)]
     pub fn test_sigmas() -> comfy_builder_core::candle::Result<()> {
         let output = run_node!(
             Custom,
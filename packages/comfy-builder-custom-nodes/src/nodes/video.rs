@@ -0,0 +1,67 @@
+//!
+//! Verify that video frames, frame rate and audio survive a Rust node
+//!
+
+use comfy_builder_core::node::Node;
+use comfy_builder_core::prelude::{Image, NodeInput, NodeOutput, Video, node};
+use std::error::Error;
+
+#[derive(NodeInput)]
+pub struct Input {
+    video: Video,
+}
+
+#[derive(NodeOutput)]
+pub struct Output {
+    video: Video,
+    frames: Image<f32>,
+}
+
+#[node]
+struct VideoReverse;
+
+impl Node for VideoReverse {
+    type In = Input;
+    type Out = Output;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn execute(&self, mut input: Self::In) -> Result<Self::Out, Self::Error> {
+        let frame_rate = input.video.frame_rate();
+        let audio = input.video.take_audio();
+        let frames = input.video.into_frames().into_tensor().flip(&[0])?;
+        let frames = Image::from_tensor(frames);
+
+        Ok(Output {
+            video: Video::from_frames(frames.clone(), frame_rate, audio)?,
+            frames,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use comfy_builder_core::candle::{Device, Tensor};
+    use comfy_builder_core::run_node;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_video_reverse() -> comfy_builder_core::candle::Result<()> {
+        let frames = Tensor::arange(0f32, 4f32, &Device::Cpu)?.reshape((4, 1, 1, 1))?;
+
+        let output = run_node!(
+            VideoReverse,
+            Input {
+                video: Video::from_frames(Image::from_tensor(frames), 8.0, None)?,
+            }
+        );
+
+        assert_eq!(output.video.frame_count()?, 4);
+        assert_eq!(output.video.frame_rate(), 8.0);
+        assert_eq!(output.video.duration()?, Duration::from_millis(500));
+        assert!(output.video.audio().is_none());
+        assert_eq!(output.frames.flatten_all()?.to_vec1::<f32>()?, vec![3.0, 2.0, 1.0, 0.0]);
+
+        Ok(())
+    }
+}
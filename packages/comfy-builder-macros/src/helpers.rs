@@ -75,6 +75,7 @@ impl<'a> FieldHelper<'a> {
         None
     }
 
+    #[allow(dead_code)]
     pub fn value_type_call(&self) -> TokenStream {
         extract_full_type_as_static_call(&self.field.ty)
     }
@@ -182,6 +183,7 @@ impl<'a> FieldHelper<'a> {
     }
 }
 
+#[allow(dead_code)]
 fn split_inner_ident(path: &TypePath) -> Option<(&Ident, &Ident)> {
     if path.path.segments.len() == 1
         && let PathArguments::AngleBracketed(angle) = &path.path.segments[0].arguments
@@ -232,6 +234,7 @@ fn extract_full_type_as_static_call(value: &Type) -> TokenStream {
     }
 }
 
+#[allow(dead_code)]
 fn first_generic_argument(ty: &Type) -> Option<Type> {
     let Type::Path(tp) = ty else { return None };
 
@@ -24,10 +24,13 @@ pub fn node_output_derive(input: TokenStream) -> TokenStream {
         placeholder,
         min,
         max,
+        step,
+        round,
         label_on,
         label_off,
         multiline,
-        control_after_generate
+        control_after_generate,
+        dtype_policy
     )
 )]
 pub fn node_input_derive(input: TokenStream) -> TokenStream {
@@ -39,6 +42,19 @@ pub fn enum_derive(input: TokenStream) -> TokenStream {
     macros::r#enum::enum_derive(input)
 }
 
+#[proc_macro_derive(DynamicOptions)]
+pub fn dynamic_options_derive(input: TokenStream) -> TokenStream {
+    macros::dynamic_options::dynamic_options_derive(input)
+}
+
+/// Use a struct as its own socket type, named after the struct or `#[io_type = "..."]`.
+///
+/// The struct must also be a `#[pyo3::pyclass]` and implement `Clone`.
+#[proc_macro_derive(ComfyCustomType, attributes(io_type))]
+pub fn custom_type_derive(input: TokenStream) -> TokenStream {
+    macros::custom_type::custom_type_derive(input)
+}
+
 #[proc_macro]
 pub fn boostrap(input: TokenStream) -> TokenStream {
     macros::boostrap::boostrap(input)
@@ -69,6 +69,9 @@ pub fn boostrap(input: TokenStream) -> TokenStream {
         #[pyo3(pass_module)]
         fn comfy_entrypoint<'py>(module: &pyo3::Bound<'py, pyo3::prelude::PyModule>) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
             let python = module.py();
+
+            comfy_builder_core::registry::set_api_version(#api_version);
+
             let base = python
                 .import(format!("comfy_api.{}", #api_version))?
                 .getattr("ComfyExtension")?;
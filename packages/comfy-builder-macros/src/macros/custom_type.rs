@@ -0,0 +1,58 @@
+use heck::ToShoutySnakeCase;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Expr, ExprLit, Lit, parse_macro_input};
+
+fn fetch_io_type(input: &DeriveInput) -> Option<String> {
+    input
+        .attrs
+        .iter()
+        .find(|attribute| attribute.path().is_ident("io_type"))
+        .and_then(|attribute| attribute.meta.require_name_value().ok())
+        .and_then(|meta| match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(content), ..
+            }) => Some(content.value()),
+            _ => None,
+        })
+}
+
+pub fn custom_type_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        panic!("ComfyCustomType can not be derived for generic types.");
+    }
+
+    let io_type = fetch_io_type(&input).unwrap_or_else(|| name.to_string().to_shouty_snake_case());
+
+    TokenStream::from(quote! {
+        // Values cross into Python as instances of the class, and are cloned back out of it.
+        const _: () = {
+            #[diagnostic::on_unimplemented(
+                message = "`{Self}` derives `ComfyCustomType`, so it must be a `#[pyo3::pyclass]` that implements `Clone`",
+                label = "add `#[pyo3::pyclass]` and `#[derive(Clone)]` to this type"
+            )]
+            trait ComfyCustomTypeRequirements {}
+
+            impl<T: pyo3::PyClass + Clone> ComfyCustomTypeRequirements for T {}
+
+            fn assert_requirements<T: ComfyCustomTypeRequirements>() {}
+
+            fn check() {
+                assert_requirements::<#name>();
+            }
+        };
+
+        inventory::submit! {
+            comfy_builder_core::registry::CustomTypeRegistration { name: #io_type }
+        }
+
+        impl<'py> comfy_builder_core::prelude::AsInput<'py> for #name {
+            fn comfy_type() -> comfy_builder_core::prelude::ComfyType {
+                comfy_builder_core::prelude::ComfyType::Custom(#io_type)
+            }
+        }
+    })
+}
@@ -0,0 +1,50 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+pub fn dynamic_options_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    match &input.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
+            _ => panic!("DynamicOptions only works on newtype structs wrapping a String"),
+        },
+        _ => panic!("DynamicOptions only works on structs"),
+    };
+
+    TokenStream::from(quote! {
+        impl<'py> pyo3::FromPyObject<'py> for #name {
+            fn extract_bound(object: &pyo3::Bound<'py, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+                use pyo3::prelude::PyAnyMethods;
+
+                let value = object.extract::<std::string::String>()?;
+
+                Ok(#name(comfy_builder_core::types::dynamic_options::validate_option::<#name>(value)?.into()))
+            }
+        }
+
+        impl<'py> pyo3::IntoPyObject<'py> for #name {
+            type Target = pyo3::PyAny;
+            type Output = pyo3::Bound<'py, Self::Target>;
+            type Error = pyo3::PyErr;
+
+            fn into_pyobject(self, python: pyo3::Python<'py>) -> Result<Self::Output, Self::Error> {
+                pyo3::IntoPyObjectExt::into_bound_py_any(self.0, python)
+            }
+        }
+
+        impl<'py> comfy_builder_core::prelude::AsInput<'py> for #name {
+            fn comfy_type() -> comfy_builder_core::prelude::ComfyType {
+                comfy_builder_core::prelude::ComfyType::Enum
+            }
+
+            fn set_options(dict: &mut pyo3::Bound<'py, pyo3::types::PyDict>, _: &pyo3::Bound<'py, pyo3::PyAny>) -> pyo3::PyResult<()> {
+                use pyo3::types::PyDictMethods;
+
+                dict.set_item("options", <#name as comfy_builder_core::prelude::DynamicOptions>::options())
+            }
+        }
+    })
+}
@@ -1,7 +1,7 @@
 use crate::helpers::FieldHelper;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Fields, parse_macro_input};
+use syn::{Data, DeriveInput, Fields, Lit, parse_macro_input};
 
 pub fn node_input_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -33,6 +33,23 @@ pub fn node_input_derive(input: TokenStream) -> TokenStream {
             .map(|label| quote! { #label })
             .unwrap_or_else(|| quote! { stringify!(#property_ident) });
 
+        let dtype_policy = named_attributes.remove("dtype_policy").map(|policy| {
+            let policy = match policy {
+                Lit::Str(policy) => policy.value(),
+                _ => panic!("`dtype_policy` must be one of \"cast\", \"strict\" or \"error\""),
+            };
+
+            match policy.as_str() {
+                "cast" => quote! { comfy_builder_core::types::torch::DTypePolicy::Cast },
+                "strict" => quote! { comfy_builder_core::types::torch::DTypePolicy::Strict },
+                "error" => quote! { comfy_builder_core::types::torch::DTypePolicy::Error },
+                other => panic!(
+                    "unknown `dtype_policy` \"{}\", expected \"cast\", \"strict\" or \"error\"",
+                    other
+                ),
+            }
+        });
+
         let attributes: Vec<proc_macro2::TokenStream> = named_attributes
             .into_iter()
             .map(|(key, value)| quote! { dict.set_item(#key, #value)?; })
@@ -44,27 +61,51 @@ pub fn node_input_derive(input: TokenStream) -> TokenStream {
                     use comfy_builder_core::prelude::AsInput;
 
                     let mut dict = pyo3::types::PyDict::new(python);
-                    let comfy_type = #value_type_call::comfy_type();
+                    let comfy_type = <#value_type_call as AsInput>::comfy_type();
 
                     #(#attributes)*
 
-                    #value_type_call::set_options(&mut dict, &io)?;
+                    <#value_type_call as AsInput>::set_options(&mut dict, &io)?;
 
                     dict.set_item("optional", #is_optional)?;
 
-                    io.getattr(comfy_type.to_string())?.getattr("Input")?.call((#display_name,), Some(&dict))?
+                    comfy_type.io_class(&io)?.getattr("Input")?.call((#display_name,), Some(&dict))?
                 }
             });
         }
 
         {
             let extract_type = field.output_ident(is_list);
-            let mut extract_logic = quote! {
-                kwargs
-                    .as_ref()
-                    .and_then(|kwargs| kwargs.get_item(#display_name).ok())
-                    .flatten()
-                    .and_then(|value| value.extract::<#extract_type>().ok())
+
+            // Tensors are converted while extracting, so the policy has to be in place around it.
+            let extract = match &dtype_policy {
+                Some(policy) => quote! { #policy.scope(|| value.extract::<#extract_type>()) },
+                None => quote! { value.extract::<#extract_type>() },
+            };
+
+            // Optional inputs holding a value that can not be extracted fall back to `None`, unless
+            // a dtype policy was set, which asks for such values to be rejected instead.
+            let mut extract_logic = if is_optional && dtype_policy.is_none() {
+                quote! {
+                    kwargs
+                        .as_ref()
+                        .and_then(|kwargs| kwargs.get_item(#display_name).ok())
+                        .flatten()
+                        .and_then(|value| #extract.ok())
+                }
+            } else {
+                quote! {
+                    kwargs
+                        .as_ref()
+                        .and_then(|kwargs| kwargs.get_item(#display_name).ok())
+                        .flatten()
+                        .map(|value| {
+                            #extract.map_err(|error| {
+                                pyo3::exceptions::PyValueError::new_err(format!("invalid value for input `{}`: {}", #display_name, error))
+                            })
+                        })
+                        .transpose()?
+                }
             };
 
             // If the user has defined **any** input as a Vec, ComfyUI will treat all inputs as lists.
@@ -1,4 +1,6 @@
 pub mod boostrap;
+pub mod custom_type;
+pub mod dynamic_options;
 pub mod r#enum;
 pub mod input;
 pub mod node;
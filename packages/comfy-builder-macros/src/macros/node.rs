@@ -109,6 +109,8 @@ pub fn node(attr: TokenStream, input: TokenStream) -> TokenStream {
         ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
             use comfy_builder_core::prelude::Out;
 
+            comfy_builder_core::types::tensor_cache::enter_execution(class.py());
+
             let instance = #ident::new();
             let input = instance.initialize_inputs(kwargs.into())?;
             let output = instance.execute(input).map_err(|error| {
@@ -123,7 +125,7 @@ pub fn node(attr: TokenStream, input: TokenStream) -> TokenStream {
                 .getattr("io")?
                 .getattr("NodeOutput")?;
 
-            node_output.call1(output.to_schema(python)?)
+            node_output.call1(#ident::OUTPUT_PLACEMENT.scope(|| output.to_schema(python))?)
         }
 
         #[pyfunction]
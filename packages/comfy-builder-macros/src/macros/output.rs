@@ -43,15 +43,17 @@ pub fn node_output_derive(input: TokenStream) -> TokenStream {
                 {
                     use comfy_builder_core::prelude::AsInput;
 
-                    let comfy_type = #value_ident::comfy_type();
-                    let dict = pyo3::types::PyDict::new(python);
+                    let comfy_type = <#value_ident as AsInput>::comfy_type();
+                    let mut dict = pyo3::types::PyDict::new(python);
 
                     dict.set_item("is_output_list", #is_list)?;
                     dict.set_item("display_name", stringify!(#property_ident))?;
 
                     #(#attributes)*
 
-                    io.getattr(comfy_type.to_string())?.getattr("Output")?.call((), Some(&dict))?
+                    <#value_ident as AsInput>::set_output_options(&mut dict, &io)?;
+
+                    comfy_type.io_class(&io)?.getattr("Output")?.call((), Some(&dict))?
                 }
             });
         }